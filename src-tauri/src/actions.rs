@@ -0,0 +1,252 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+pub(crate) const ACTIONS_CHANGED_EVENT: &str = "notification-actions-changed";
+/// JSON-RPC notification sent to the MCP session that attached the actions.
+pub(crate) const ACTION_RESULT_METHOD: &str = "notifications/agent-notifier/action";
+pub(crate) const MAX_ACTIONS: usize = 4;
+const MAX_LABEL_CHARS: usize = 40;
+const MAX_COPY_CHARS: usize = 10_000;
+// Only the most recent actionable notifications stay on screen and pollable.
+const MAX_TRACKED: usize = 100;
+/// Action id recorded when the notification itself is clicked to open its `url`.
+pub(crate) const DEFAULT_ACTION_ID: &str = "default";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ActionKind {
+    OpenUrl {
+        url: String,
+    },
+    /// Shows `path` in the file manager.
+    OpenPath {
+        path: String,
+    },
+    Copy {
+        text: String,
+    },
+    /// Reports the press back to the agent, optionally with typed text.
+    Reply {
+        #[serde(default)]
+        allow_text: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct NotificationAction {
+    /// Assigned in order ("1", "2", ...) when left out.
+    #[serde(default)]
+    pub id: String,
+    pub label: String,
+    #[serde(flatten)]
+    pub kind: ActionKind,
+}
+
+/// Checks caller-supplied actions and fills in missing ids.
+pub(crate) fn validate_actions(actions: &mut [NotificationAction]) -> Result<(), String> {
+    if actions.len() > MAX_ACTIONS {
+        return Err(format!("at most {MAX_ACTIONS} actions are supported"));
+    }
+    let mut ids = HashSet::new();
+    for (index, action) in actions.iter_mut().enumerate() {
+        action.label = action.label.trim().to_owned();
+        if action.label.is_empty() || action.label.chars().count() > MAX_LABEL_CHARS {
+            return Err(format!(
+                "action labels must be 1 to {MAX_LABEL_CHARS} characters"
+            ));
+        }
+        if action.id.trim().is_empty() {
+            action.id = (index + 1).to_string();
+        }
+        if action.id == DEFAULT_ACTION_ID || !ids.insert(action.id.clone()) {
+            return Err(format!("action id '{}' is reserved or repeated", action.id));
+        }
+        match &action.kind {
+            ActionKind::OpenUrl { url } => validate_url(url)?,
+            ActionKind::OpenPath { path } => {
+                if !Path::new(path).is_absolute() {
+                    return Err(format!("'{path}' must be an absolute path"));
+                }
+            }
+            ActionKind::Copy { text } => {
+                if text.is_empty() || text.chars().count() > MAX_COPY_CHARS {
+                    return Err(format!(
+                        "copy text must be 1 to {MAX_COPY_CHARS} characters"
+                    ));
+                }
+            }
+            ActionKind::Reply { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+/// Links are opened in the browser, so only web URLs are accepted.
+pub(crate) fn validate_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|err| format!("'{url}' is not a URL: {err}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("'{url}' must be an http or https URL"));
+    }
+    Ok(())
+}
+
+/// A button press (or click-through) reported back to the agent.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ActionResult {
+    pub action_id: String,
+    pub label: String,
+    pub text: Option<String>,
+    /// Unix timestamp in milliseconds.
+    pub at: u64,
+}
+
+/// A notification with actions or a link, as shown in the settings window.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ActionableNotification {
+    pub id: String,
+    pub agent: String,
+    pub title: String,
+    pub content: String,
+    pub url: Option<String>,
+    pub actions: Vec<NotificationAction>,
+    /// Unix timestamp in milliseconds.
+    pub created_at: u64,
+    pub results: Vec<ActionResult>,
+    /// Hidden from the window; results can still be polled.
+    pub dismissed: bool,
+    /// MCP session that sent it, which is told about each result.
+    #[serde(skip)]
+    pub session: Option<String>,
+}
+
+/// What happened when the user picked an action, for the caller to act on.
+pub(crate) struct Invoked {
+    pub kind: Option<ActionKind>,
+    pub url: Option<String>,
+    pub result: ActionResult,
+    pub session: Option<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct ActionRegistry {
+    entries: Mutex<VecDeque<ActionableNotification>>,
+}
+
+impl ActionRegistry {
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<ActionableNotification>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn track(&self, app: &AppHandle, notification: ActionableNotification) {
+        {
+            let mut entries = self.lock();
+            entries.push_back(notification);
+            while entries.len() > MAX_TRACKED {
+                entries.pop_front();
+            }
+        }
+        notify_changed(app);
+    }
+
+    /// Notifications still shown in the window, newest first.
+    pub fn list(&self) -> Vec<ActionableNotification> {
+        self.lock()
+            .iter()
+            .rev()
+            .filter(|entry| !entry.dismissed)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<ActionableNotification> {
+        self.lock().iter().find(|entry| entry.id == id).cloned()
+    }
+
+    /// Records that the user picked `action_id`, or clicked through to the link when `None`.
+    pub fn invoke(
+        &self,
+        app: &AppHandle,
+        id: &str,
+        action_id: Option<&str>,
+        text: Option<String>,
+    ) -> Result<Invoked, String> {
+        let invoked = {
+            let mut entries = self.lock();
+            let entry = entries
+                .iter_mut()
+                .find(|entry| entry.id == id)
+                .ok_or_else(|| "This notification is no longer available".to_owned())?;
+            let (action_id, label, kind) = match action_id {
+                None => {
+                    if entry.url.is_none() {
+                        return Err("This notification has no link".into());
+                    }
+                    (DEFAULT_ACTION_ID.to_owned(), "Open link".to_owned(), None)
+                }
+                Some(action_id) => {
+                    let action = entry
+                        .actions
+                        .iter()
+                        .find(|action| action.id == action_id)
+                        .ok_or_else(|| format!("Unknown action '{action_id}'"))?;
+                    (
+                        action.id.clone(),
+                        action.label.clone(),
+                        Some(action.kind.clone()),
+                    )
+                }
+            };
+            let text = match &kind {
+                Some(ActionKind::Reply { allow_text: true }) => text
+                    .map(|text| text.trim().to_owned())
+                    .filter(|text| !text.is_empty()),
+                _ => None,
+            };
+            let result = ActionResult {
+                action_id,
+                label,
+                text,
+                at: crate::unix_millis_now(),
+            };
+            entry.results.push(result.clone());
+            Invoked {
+                kind,
+                url: entry.url.clone(),
+                result,
+                session: entry.session.clone(),
+            }
+        };
+        notify_changed(app);
+        Ok(invoked)
+    }
+
+    /// Keeps the card in the window in step with an updated notification.
+    pub fn update(&self, app: &AppHandle, id: &str, title: &str, content: &str) {
+        {
+            let mut entries = self.lock();
+            let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) else {
+                return;
+            };
+            entry.title = title.to_owned();
+            entry.content = content.to_owned();
+        }
+        notify_changed(app);
+    }
+
+    pub fn dismiss(&self, app: &AppHandle, id: &str) {
+        if let Some(entry) = self.lock().iter_mut().find(|entry| entry.id == id) {
+            entry.dismissed = true;
+        }
+        notify_changed(app);
+    }
+}
+
+fn notify_changed(app: &AppHandle) {
+    if let Err(err) = app.emit(ACTIONS_CHANGED_EVENT, ()) {
+        eprintln!("Failed to emit {ACTIONS_CHANGED_EVENT}: {err}");
+    }
+}
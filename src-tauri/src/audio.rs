@@ -0,0 +1,478 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rodio::cpal::traits::HostTrait;
+use rodio::{Decoder, DeviceTrait, OutputStream, Sink, Source};
+use serde::{Deserialize, Serialize};
+
+use crate::levels::LevelSettings;
+
+pub(crate) const AUDIO_SETTINGS_KEY: &str = "audio";
+pub(crate) const MUTE_CHANGED_EVENT: &str = "mute-changed";
+const DEFAULT_SOUND: &str = "Ping";
+const SUPPORTED_EXTENSIONS: [&str; 4] = ["wav", "ogg", "flac", "mp3"];
+// Every sound is held decoded in memory; keep notification sounds notification-sized.
+const MAX_SOUND_FILE_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_SOUND_SECONDS: usize = 30;
+// Plays beyond this many waiting in line are dropped rather than queued up for minutes.
+const MAX_QUEUED_SOUNDS: usize = 3;
+const IDLE_CLOSE_AFTER: Duration = Duration::from_secs(30);
+// Slack before a sink that stopped draining is treated as a dead device.
+const STALL_GRACE: Duration = Duration::from_secs(2);
+
+// Keep the notification sounds embedded so they ship with the app.
+const BUNDLED_SOUNDS: [(&str, &[u8]); 5] = [
+    ("Ping", include_bytes!("../sounds/Ping.wav")),
+    ("Glass", include_bytes!("../sounds/Glass.wav")),
+    ("Funk", include_bytes!("../sounds/Funk.wav")),
+    ("Basso", include_bytes!("../sounds/Basso.wav")),
+    ("Sosumi", include_bytes!("../sounds/Sosumi.wav")),
+];
+
+/// Output and sound choices that are not tied to a level. A sound is either the name of a
+/// bundled sound or a path to a wav, ogg, flac or mp3 file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AudioSettings {
+    /// Silences notifications; previews from the settings window still play.
+    #[serde(default)]
+    pub muted: bool,
+    /// Playback volume from 0.0 to 1.0.
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// Output device name as the system reports it; the default device when unset.
+    #[serde(default)]
+    pub output_device: Option<String>,
+    /// Played when neither the agent nor the level picks a sound; Ping when unset.
+    #[serde(default)]
+    pub default_sound: Option<String>,
+    /// Per-agent sounds, which win over the level's sound.
+    #[serde(default)]
+    pub agent_sounds: BTreeMap<String, String>,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            volume: default_volume(),
+            output_device: None,
+            default_sound: None,
+            agent_sounds: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+impl AudioSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.volume) {
+            return Err("Volume must be between 0 and 1".into());
+        }
+        if self
+            .output_device
+            .as_deref()
+            .is_some_and(|device| device.trim().is_empty())
+        {
+            return Err("Output device name cannot be empty".into());
+        }
+        if let Some(sound) = &self.default_sound {
+            decode_sound(sound)?;
+        }
+        for (agent, sound) in &self.agent_sounds {
+            if agent.trim().is_empty() {
+                return Err("Agent sounds need an agent name".into());
+            }
+            decode_sound(sound).map_err(|err| format!("Sound for '{agent}': {err}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Fully decoded PCM, so playing a sound never touches the disk or the decoder again.
+#[derive(Clone)]
+pub(crate) struct DecodedSound {
+    channels: u16,
+    sample_rate: u32,
+    samples: Arc<Vec<i16>>,
+    /// Multiplies the output volume for this one play.
+    gain: f32,
+}
+
+impl DecodedSound {
+    /// The same sound played `gain` times as loud, e.g. for escalating reminders.
+    pub fn louder(self, gain: f32) -> Self {
+        Self { gain, ..self }
+    }
+
+    fn duration(&self) -> Duration {
+        clip_duration(&self.samples, self.channels, self.sample_rate)
+    }
+}
+
+fn clip_duration(samples: &[i16], channels: u16, sample_rate: u32) -> Duration {
+    let frames = samples.len() as f64 / channels.max(1) as f64;
+    Duration::from_secs_f64(frames / sample_rate.max(1) as f64)
+}
+
+/// Plays a decoded sound straight from its shared samples, so each play costs a pointer
+/// copy rather than a copy of the whole clip.
+struct SharedSamples {
+    channels: u16,
+    sample_rate: u32,
+    samples: Arc<Vec<i16>>,
+    position: usize,
+}
+
+impl From<&DecodedSound> for SharedSamples {
+    fn from(sound: &DecodedSound) -> Self {
+        Self {
+            channels: sound.channels,
+            sample_rate: sound.sample_rate,
+            samples: sound.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for SharedSamples {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        let sample = self.samples.get(self.position).copied()?;
+        self.position += 1;
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.samples.len() - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+impl Source for SharedSamples {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(clip_duration(
+            &self.samples,
+            self.channels,
+            self.sample_rate,
+        ))
+    }
+}
+
+/// Every sound the current settings can ask for, decoded once when the settings load.
+pub(crate) struct SoundBank {
+    settings: AudioSettings,
+    decoded: HashMap<String, DecodedSound>,
+}
+
+impl SoundBank {
+    /// Decodes everything the settings reference. Files that are missing or unreadable are
+    /// logged and left out, so lookups fall back to the embedded sound instead.
+    pub fn load(settings: AudioSettings, levels: &LevelSettings) -> Self {
+        let mut decoded = HashMap::new();
+        let referenced = BUNDLED_SOUNDS
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(settings.default_sound.as_deref())
+            .chain(settings.agent_sounds.values().map(String::as_str))
+            .chain(levels.sounds());
+        for sound in referenced {
+            if decoded.contains_key(sound) {
+                continue;
+            }
+            match decode_sound(sound) {
+                Ok(pcm) => {
+                    decoded.insert(sound.to_owned(), pcm);
+                }
+                Err(err) => eprintln!("Falling back to the default sound for '{sound}': {err}"),
+            }
+        }
+
+        Self { settings, decoded }
+    }
+
+    pub fn settings(&self) -> &AudioSettings {
+        &self.settings
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.settings.muted = muted;
+    }
+
+    /// Picks the agent's sound, then the level's, then the default sound.
+    pub fn resolve(&self, agent: &str, level_sound: Option<&str>) -> DecodedSound {
+        self.settings
+            .agent_sounds
+            .get(agent)
+            .map(String::as_str)
+            .into_iter()
+            .chain(level_sound)
+            .chain(self.settings.default_sound.as_deref())
+            .find_map(|sound| self.decoded.get(sound))
+            // Bundled sounds always decode, so the embedded default is always there.
+            .unwrap_or_else(|| &self.decoded[DEFAULT_SOUND])
+            .clone()
+    }
+}
+
+pub(crate) fn bundled_sound_names() -> Vec<&'static str> {
+    BUNDLED_SOUNDS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Decodes a bundled sound by name, or an audio file by path.
+pub(crate) fn decode_sound(sound: &str) -> Result<DecodedSound, String> {
+    if let Some((_, bytes)) = BUNDLED_SOUNDS.iter().find(|(name, _)| *name == sound) {
+        return decode(Cursor::new(*bytes));
+    }
+
+    let path = Path::new(sound);
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!(
+            "'{sound}' is not a bundled sound or a .wav, .ogg, .flac or .mp3 file"
+        ));
+    }
+
+    let file = File::open(path).map_err(|err| format!("Cannot open '{sound}': {err}"))?;
+    let size = file
+        .metadata()
+        .map_err(|err| format!("Cannot read '{sound}': {err}"))?
+        .len();
+    if size > MAX_SOUND_FILE_BYTES {
+        return Err(format!("'{sound}' is larger than 10 MB"));
+    }
+    decode(BufReader::new(file)).map_err(|err| format!("'{sound}': {err}"))
+}
+
+fn decode<R: Read + Seek + Send + Sync + 'static>(reader: R) -> Result<DecodedSound, String> {
+    let decoder = Decoder::new(reader).map_err(|err| format!("Failed to decode sound: {err}"))?;
+    let channels = decoder.channels();
+    let sample_rate = decoder.sample_rate();
+    let max_samples = channels as usize * sample_rate as usize * MAX_SOUND_SECONDS;
+    let samples: Vec<i16> = decoder.take(max_samples).collect();
+    if samples.is_empty() {
+        return Err("Sound contains no audio".into());
+    }
+
+    Ok(DecodedSound {
+        channels,
+        sample_rate,
+        samples: Arc::new(samples),
+        gain: 1.0,
+    })
+}
+
+/// Names of the output devices the system currently offers.
+pub(crate) fn output_device_names() -> Vec<String> {
+    match rodio::cpal::default_host().output_devices() {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(err) => {
+            eprintln!("Failed to list audio output devices: {err}");
+            Vec::new()
+        }
+    }
+}
+
+/// The part of [`AudioSettings`] the worker thread needs.
+#[derive(Debug, Clone, PartialEq)]
+struct OutputConfig {
+    volume: f32,
+    device: Option<String>,
+}
+
+impl From<&AudioSettings> for OutputConfig {
+    fn from(settings: &AudioSettings) -> Self {
+        Self {
+            volume: settings.volume,
+            device: settings.output_device.clone(),
+        }
+    }
+}
+
+enum AudioCommand {
+    Play(DecodedSound),
+    Configure(OutputConfig),
+}
+
+/// Owns the audio output on a dedicated thread so notifications never open a device of their
+/// own. Plays are queued one after another rather than mixed on top of each other.
+pub(crate) struct AudioPlayer {
+    sender: mpsc::Sender<AudioCommand>,
+}
+
+impl AudioPlayer {
+    pub fn spawn(settings: &AudioSettings) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let config = OutputConfig::from(settings);
+        // rodio's output stream cannot move between threads, so it lives and dies on this one.
+        let spawned = thread::Builder::new()
+            .name("audio-output".into())
+            .spawn(move || run_audio_worker(receiver, config));
+        if let Err(err) = spawned {
+            eprintln!("Failed to start audio worker: {err}");
+        }
+        Self { sender }
+    }
+
+    pub fn play(&self, sound: DecodedSound) {
+        self.send(AudioCommand::Play(sound));
+    }
+
+    /// Applies new volume and device choices; muting is decided before [`Self::play`].
+    pub fn configure(&self, settings: &AudioSettings) {
+        self.send(AudioCommand::Configure(settings.into()));
+    }
+
+    fn send(&self, command: AudioCommand) {
+        if self.sender.send(command).is_err() {
+            eprintln!("Audio worker is not running");
+        }
+    }
+}
+
+/// The configured device if it is plugged in, otherwise the system default.
+fn resolve_device(preferred: Option<&str>) -> Option<rodio::Device> {
+    let host = rodio::cpal::default_host();
+    if let Some(preferred) = preferred {
+        let found = host.output_devices().ok().and_then(|mut devices| {
+            devices.find(|device| device.name().ok().as_deref() == Some(preferred))
+        });
+        if found.is_some() {
+            return found;
+        }
+    }
+    host.default_output_device()
+}
+
+struct AudioOutput {
+    // Dropping the stream closes the device, so it is kept alongside the sink.
+    _stream: OutputStream,
+    sink: Sink,
+    device_name: Option<String>,
+    /// When everything queued so far should have finished playing.
+    drains_at: Instant,
+    /// The most recently queued sound, used to merge repeats.
+    last_queued: Option<Arc<Vec<i16>>>,
+}
+
+impl AudioOutput {
+    fn open(config: &OutputConfig) -> Result<Self, String> {
+        let device = resolve_device(config.device.as_deref()).ok_or("No audio output device")?;
+        let device_name = device.name().ok();
+        if let Some(preferred) = &config.device {
+            if device_name.as_ref() != Some(preferred) {
+                eprintln!("Output device '{preferred}' not found; using the default device");
+            }
+        }
+        let (stream, handle) = OutputStream::try_from_device(&device)
+            .map_err(|err| format!("Audio output init failed: {err}"))?;
+        let sink =
+            Sink::try_new(&handle).map_err(|err| format!("Failed to create audio sink: {err}"))?;
+        sink.set_volume(config.volume);
+
+        Ok(Self {
+            _stream: stream,
+            sink,
+            device_name,
+            drains_at: Instant::now(),
+            last_queued: None,
+        })
+    }
+
+    /// Whether the output should be reopened: a different device should now be used (e.g.
+    /// headphones were unplugged, or the chosen device came back) or playback has stopped
+    /// draining, which is how a vanished device shows up.
+    fn is_stale(&self, config: &OutputConfig) -> bool {
+        let stalled = !self.sink.empty() && Instant::now() > self.drains_at + STALL_GRACE;
+        let target = resolve_device(config.device.as_deref()).and_then(|device| device.name().ok());
+        stalled || target != self.device_name
+    }
+
+    fn enqueue(&mut self, sound: DecodedSound) {
+        let busy = !self.sink.empty();
+        // A burst of identical notifications should sound like one, not a drum roll.
+        let repeat = self
+            .last_queued
+            .as_ref()
+            .is_some_and(|last| Arc::ptr_eq(last, &sound.samples));
+        if busy && (repeat || self.sink.len() >= MAX_QUEUED_SOUNDS) {
+            return;
+        }
+
+        let now = Instant::now();
+        self.drains_at = self.drains_at.max(now) + sound.duration();
+        self.last_queued = Some(sound.samples.clone());
+        self.sink
+            .append(SharedSamples::from(&sound).amplify(sound.gain));
+    }
+}
+
+fn run_audio_worker(receiver: mpsc::Receiver<AudioCommand>, mut config: OutputConfig) {
+    let mut output: Option<AudioOutput> = None;
+    loop {
+        let sound = match receiver.recv_timeout(IDLE_CLOSE_AFTER) {
+            Ok(AudioCommand::Play(sound)) => sound,
+            Ok(AudioCommand::Configure(next)) => {
+                if next.device != config.device {
+                    output = None;
+                } else if let Some(output) = &output {
+                    output.sink.set_volume(next.volume);
+                }
+                config = next;
+                continue;
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // Let the device sleep between bursts; it is reopened on the next play.
+                if output.as_ref().is_some_and(|output| output.sink.empty()) {
+                    output = None;
+                }
+                continue;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        };
+
+        if output
+            .as_ref()
+            .is_some_and(|output| output.is_stale(&config))
+        {
+            output = None;
+        }
+        if output.is_none() {
+            match AudioOutput::open(&config) {
+                Ok(opened) => output = Some(opened),
+                Err(err) => {
+                    // Try again on the next play; the device may come back.
+                    eprintln!("{err}");
+                    continue;
+                }
+            }
+        }
+        if let Some(output) = output.as_mut() {
+            output.enqueue(sound);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::AppState;
+
+pub(crate) const AUTH_SETTINGS_KEY: &str = "apiTokens";
+// Prefix makes leaked tokens easy to spot in logs and secret scanners.
+const TOKEN_PREFIX: &str = "an_";
+
+/// How a token's label relates to the `agent` field of the notifications it sends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AgentPolicy {
+    /// Replace whatever `agent` the caller sent with the token label.
+    #[default]
+    Override,
+    /// Reject notifications whose `agent` does not match the token label.
+    Enforce,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ApiToken {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub agent_policy: AgentPolicy,
+    /// SHA-256 of the secret; the plaintext is only returned once, on creation.
+    pub secret_hash: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct AuthSettings {
+    /// When false, requests without a token are still accepted.
+    #[serde(default)]
+    pub require_token: bool,
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+}
+
+/// Token metadata that is safe to hand to the settings window.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ApiTokenSummary {
+    pub id: String,
+    pub label: String,
+    pub agent_policy: AgentPolicy,
+    pub created_at: u64,
+}
+
+impl From<&ApiToken> for ApiTokenSummary {
+    fn from(token: &ApiToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            label: token.label.clone(),
+            agent_policy: token.agent_policy,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CreatedApiToken {
+    #[serde(flatten)]
+    pub summary: ApiTokenSummary,
+    pub secret: String,
+}
+
+/// Identity attached to a request that presented a valid bearer token.
+#[derive(Debug, Clone)]
+pub(crate) struct TokenIdentity {
+    pub label: String,
+    pub agent_policy: AgentPolicy,
+}
+
+impl TokenIdentity {
+    /// Apply the token's agent policy to the `agent` supplied by the caller.
+    pub fn resolve_agent(&self, agent: &str) -> Result<String, String> {
+        match self.agent_policy {
+            AgentPolicy::Override => Ok(self.label.clone()),
+            AgentPolicy::Enforce if agent.trim() == self.label => Ok(self.label.clone()),
+            AgentPolicy::Enforce => Err(format!("'agent' must be '{}' for this token", self.label)),
+        }
+    }
+}
+
+pub(crate) fn create_token(
+    settings: &mut AuthSettings,
+    label: &str,
+    agent_policy: AgentPolicy,
+) -> Result<CreatedApiToken, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Token label cannot be empty".into());
+    }
+
+    let secret = format!(
+        "{TOKEN_PREFIX}{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let token = ApiToken {
+        id: uuid::Uuid::new_v4().to_string(),
+        label: label.to_owned(),
+        agent_policy,
+        secret_hash: hash_secret(&secret),
+        created_at: crate::unix_millis_now(),
+    };
+    let summary = ApiTokenSummary::from(&token);
+    settings.tokens.push(token);
+
+    Ok(CreatedApiToken { summary, secret })
+}
+
+pub(crate) fn revoke_token(settings: &mut AuthSettings, id: &str) -> Result<(), String> {
+    let before = settings.tokens.len();
+    settings.tokens.retain(|token| token.id != id);
+    if settings.tokens.len() == before {
+        return Err(format!("No token with id '{id}'"));
+    }
+    // Otherwise every request would be rejected, a state enabling the requirement refuses.
+    if settings.require_token && settings.tokens.is_empty() {
+        return Err("Stop requiring a token before revoking the last one".into());
+    }
+    Ok(())
+}
+
+fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+fn unauthorized(message: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(json!({ "message": message })),
+    )
+        .into_response()
+}
+
+/// Validates `Authorization: Bearer <token>` and attaches a [`TokenIdentity`] to the request.
+pub(crate) async fn require_bearer_token(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .map(|value| value.to_str().unwrap_or_default().to_owned());
+
+    let identity = {
+        let auth = state.auth.read().await;
+        match presented {
+            Some(value) => {
+                let Some(secret) = value
+                    .strip_prefix("Bearer ")
+                    .map(str::trim)
+                    .filter(|secret| !secret.is_empty())
+                else {
+                    return unauthorized("Authorization header must use the Bearer scheme");
+                };
+
+                let hash = hash_secret(secret);
+                let Some(token) = auth.tokens.iter().find(|token| token.secret_hash == hash) else {
+                    return unauthorized("Invalid or revoked token");
+                };
+
+                Some(TokenIdentity {
+                    label: token.label.clone(),
+                    agent_policy: token.agent_policy,
+                })
+            }
+            None if auth.require_token => return unauthorized("A bearer token is required"),
+            None => None,
+        }
+    };
+
+    if let Some(identity) = identity {
+        request.extensions_mut().insert(identity);
+    }
+
+    next.run(request).await
+}
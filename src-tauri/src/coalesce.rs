@@ -0,0 +1,172 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Notification;
+
+pub(crate) const COALESCE_SETTINGS_KEY: &str = "coalescing";
+// The longest a burst can keep growing before its summary is shown anyway.
+const MAX_BURST_HOLD_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct CoalesceSettings {
+    /// Identical notifications (same agent, title and content) inside this window are dropped.
+    /// 0 turns deduplication off.
+    pub dedupe_window_seconds: u64,
+    /// Notifications from one agent closer together than this count towards a burst.
+    /// 0 turns burst coalescing off.
+    pub burst_window_seconds: u64,
+    /// How many notifications inside the burst window start merging the rest into a summary.
+    pub burst_threshold: usize,
+}
+
+impl Default for CoalesceSettings {
+    fn default() -> Self {
+        Self {
+            dedupe_window_seconds: 30,
+            burst_window_seconds: 10,
+            burst_threshold: 3,
+        }
+    }
+}
+
+impl CoalesceSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.dedupe_window_seconds > 3600 || self.burst_window_seconds > 3600 {
+            return Err("Windows can be at most an hour".into());
+        }
+        if self.burst_threshold < 2 {
+            return Err("A burst needs at least 2 notifications".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct AgentBurst {
+    /// When recent notifications from this agent were shown.
+    recent: VecDeque<u64>,
+    /// Held back for the summary.
+    pending: Vec<Notification>,
+    started_at: u64,
+    flush_at: u64,
+}
+
+struct CoalesceState {
+    settings: CoalesceSettings,
+    /// When each agent/title/content combination was last shown.
+    seen: HashMap<(String, String, String), u64>,
+    bursts: HashMap<String, AgentBurst>,
+}
+
+/// Drops repeats of the same notification and merges rapid-fire notifications from one agent
+/// into a single summary.
+pub(crate) struct Coalescer {
+    state: Mutex<CoalesceState>,
+}
+
+impl Coalescer {
+    pub fn new(settings: CoalesceSettings) -> Self {
+        Self {
+            state: Mutex::new(CoalesceState {
+                settings,
+                seen: HashMap::new(),
+                bursts: HashMap::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CoalesceState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn settings(&self) -> CoalesceSettings {
+        self.lock().settings.clone()
+    }
+
+    pub fn set_settings(&self, settings: CoalesceSettings) {
+        self.lock().settings = settings;
+    }
+
+    /// Whether this exact notification was already let through within the dedupe window.
+    pub fn is_duplicate(&self, notification: &Notification, now: u64) -> bool {
+        let mut state = self.lock();
+        let window = state.settings.dedupe_window_seconds * 1000;
+        if window == 0 {
+            return false;
+        }
+        state
+            .seen
+            .retain(|_, shown| now.saturating_sub(*shown) < window);
+        let key = (
+            notification.agent.clone(),
+            notification.title.clone(),
+            notification.content.clone(),
+        );
+        if state.seen.contains_key(&key) {
+            return true;
+        }
+        // Only the first one counts, so a notification repeated forever still shows once per
+        // window.
+        state.seen.insert(key, now);
+        false
+    }
+
+    /// Holds the notification back for a burst summary if its agent has been busy. Returns
+    /// whether it was merged.
+    pub fn merge_into_burst(&self, notification: &Notification, now: u64) -> bool {
+        let mut state = self.lock();
+        let window = state.settings.burst_window_seconds * 1000;
+        let threshold = state.settings.burst_threshold;
+        if window == 0 {
+            return false;
+        }
+        let burst = state.bursts.entry(notification.agent.clone()).or_default();
+        burst.forget_before(now, window);
+
+        if burst.pending.is_empty() && burst.recent.len() + 1 < threshold {
+            burst.recent.push_back(now);
+            return false;
+        }
+        if burst.pending.is_empty() {
+            burst.started_at = now;
+        }
+        burst.pending.push(notification.clone());
+        // Wait for the burst to go quiet, but not forever.
+        burst.flush_at = (now + window).min(burst.started_at + MAX_BURST_HOLD_MS);
+        true
+    }
+
+    /// Hands back every burst that has gone quiet, keyed by agent.
+    pub fn take_due(&self, now: u64) -> Vec<(String, Vec<Notification>)> {
+        let mut state = self.lock();
+        let window = state.settings.burst_window_seconds * 1000;
+        let mut due = Vec::new();
+        for (agent, burst) in state.bursts.iter_mut() {
+            burst.forget_before(now, window);
+            if !burst.pending.is_empty() && burst.flush_at <= now {
+                // The summary itself counts as one shown notification.
+                burst.recent.push_back(now);
+                due.push((agent.clone(), std::mem::take(&mut burst.pending)));
+            }
+        }
+        state
+            .bursts
+            .retain(|_, burst| !burst.pending.is_empty() || !burst.recent.is_empty());
+        due
+    }
+}
+
+impl AgentBurst {
+    fn forget_before(&mut self, now: u64, window: u64) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|shown| now.saturating_sub(*shown) >= window)
+        {
+            self.recent.pop_front();
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+
+use crate::audio;
+use crate::levels::NotificationLevel;
+use crate::sinks::Sink;
+use crate::Notification;
+
+pub(crate) const ESCALATION_SETTINGS_KEY: &str = "escalation";
+pub(crate) const ESCALATIONS_CHANGED_EVENT: &str = "escalations-changed";
+const MIN_INTERVAL_SECS: u64 = 30;
+const MAX_INTERVAL_SECS: u64 = 24 * 60 * 60;
+const MAX_REMINDERS: u32 = 10;
+// Each reminder plays this much louder than the one before, up to the cap.
+const GAIN_STEP: f32 = 0.5;
+const MAX_GAIN: f32 = 3.0;
+// Unacknowledged notifications beyond this are dropped oldest first.
+const MAX_PENDING: usize = 100;
+
+/// Which notifications must be acknowledged, and what happens while they are not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct EscalationPolicy {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Only notifications from this agent; every agent when unset.
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default = "default_levels")]
+    pub levels: Vec<NotificationLevel>,
+    /// Time between reminders, and before the sink fires after the last one.
+    #[serde(default = "default_interval")]
+    pub interval_seconds: u64,
+    #[serde(default = "default_reminders")]
+    pub reminders: u32,
+    /// Played for reminders; the notification's usual sound when unset.
+    #[serde(default)]
+    pub sound: Option<String>,
+    /// Fired once the reminders run out without an acknowledgement.
+    #[serde(default)]
+    pub sink: Option<Sink>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_levels() -> Vec<NotificationLevel> {
+    vec![NotificationLevel::Critical]
+}
+
+fn default_interval() -> u64 {
+    5 * 60
+}
+
+fn default_reminders() -> u32 {
+    3
+}
+
+impl EscalationPolicy {
+    fn applies_to(&self, notification: &Notification) -> bool {
+        self.enabled
+            && self.levels.contains(&notification.level)
+            && self
+                .agent
+                .as_deref()
+                .is_none_or(|agent| agent == notification.agent)
+    }
+}
+
+/// Escalation policies, stored under [`ESCALATION_SETTINGS_KEY`]. The first enabled policy
+/// that matches a notification applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct EscalationSettings {
+    pub policies: Vec<EscalationPolicy>,
+}
+
+impl Default for EscalationSettings {
+    fn default() -> Self {
+        Self {
+            policies: vec![EscalationPolicy {
+                name: "Critical notifications".into(),
+                enabled: true,
+                agent: None,
+                levels: default_levels(),
+                interval_seconds: default_interval(),
+                reminders: default_reminders(),
+                sound: None,
+                sink: None,
+            }],
+        }
+    }
+}
+
+impl EscalationSettings {
+    /// Checks every policy. Decodes custom sounds, so call it off the async workers.
+    pub fn validate(&mut self) -> Result<(), String> {
+        for policy in &mut self.policies {
+            policy.name = policy.name.trim().to_owned();
+            policy.agent = policy
+                .agent
+                .take()
+                .map(|agent| agent.trim().to_owned())
+                .filter(|agent| !agent.is_empty());
+            let name = if policy.name.is_empty() {
+                "Unnamed policy".to_owned()
+            } else {
+                policy.name.clone()
+            };
+            if policy.levels.is_empty() {
+                return Err(format!("'{name}' needs at least one level"));
+            }
+            if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&policy.interval_seconds) {
+                return Err(format!(
+                    "'{name}' needs an interval between {MIN_INTERVAL_SECS} and {MAX_INTERVAL_SECS} seconds"
+                ));
+            }
+            if policy.reminders > MAX_REMINDERS {
+                return Err(format!(
+                    "'{name}' can send at most {MAX_REMINDERS} reminders"
+                ));
+            }
+            if let Some(sound) = &policy.sound {
+                audio::decode_sound(sound).map_err(|err| format!("Sound for '{name}': {err}"))?;
+            }
+            if let Some(sink) = &policy.sink {
+                sink.validate().map_err(|err| format!("'{name}': {err}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A delivered notification that has not been acknowledged yet.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Escalation {
+    /// The notification's id.
+    pub id: String,
+    pub agent: String,
+    pub title: String,
+    pub content: String,
+    pub level: NotificationLevel,
+    /// Unix timestamp in milliseconds.
+    pub delivered_at: u64,
+    pub reminders_sent: u32,
+    /// When the next reminder, or the sink, is due; unix milliseconds.
+    pub next_at: u64,
+    pub policy: EscalationPolicy,
+}
+
+impl Escalation {
+    /// What sinks receive when nobody acknowledged the notification.
+    pub fn sink_payload(&self, now: u64) -> Value {
+        json!({
+            "id": self.id,
+            "title": self.title,
+            "content": self.content,
+            "agent": self.agent,
+            "level": self.level,
+            "escalation": self.policy.name,
+            "reminders": self.reminders_sent,
+            "delivered_at": self.delivered_at,
+            "timestamp": now,
+        })
+    }
+}
+
+/// What the escalation loop should do next for an unacknowledged notification.
+pub(crate) enum Step {
+    /// Show it again and play the sound `gain` times as loud.
+    Remind(Escalation, f32),
+    /// The reminders ran out; hand it to the policy's sink.
+    HandOff(Escalation, Sink),
+}
+
+struct EscalationState {
+    settings: EscalationSettings,
+    pending: Vec<Escalation>,
+}
+
+/// Notifications waiting to be acknowledged, and the policies that decide which ones are.
+pub(crate) struct Escalations {
+    state: Mutex<EscalationState>,
+}
+
+impl Escalations {
+    pub fn new(settings: EscalationSettings) -> Self {
+        Self {
+            state: Mutex::new(EscalationState {
+                settings,
+                pending: Vec::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, EscalationState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn settings(&self) -> EscalationSettings {
+        self.lock().settings.clone()
+    }
+
+    /// Applies to notifications delivered from now on; pending ones keep their policy.
+    pub fn set_settings(&self, settings: EscalationSettings) {
+        self.lock().settings = settings;
+    }
+
+    /// The policy a notification falls under, if it has to be acknowledged.
+    pub fn policy_for(&self, notification: &Notification) -> Option<EscalationPolicy> {
+        self.lock()
+            .settings
+            .policies
+            .iter()
+            .find(|policy| policy.applies_to(notification))
+            .cloned()
+    }
+
+    /// Starts waiting for a delivered notification to be acknowledged.
+    pub fn track(
+        &self,
+        app: &AppHandle,
+        id: &str,
+        notification: &Notification,
+        policy: EscalationPolicy,
+        now: u64,
+    ) {
+        {
+            let mut state = self.lock();
+            state.pending.push(Escalation {
+                id: id.to_owned(),
+                agent: notification.agent.clone(),
+                title: notification.title.clone(),
+                content: notification.content.clone(),
+                level: notification.level,
+                delivered_at: now,
+                reminders_sent: 0,
+                next_at: now + policy.interval_seconds * 1000,
+                policy,
+            });
+            if state.pending.len() > MAX_PENDING {
+                state.pending.remove(0);
+            }
+        }
+        changed(app);
+    }
+
+    pub fn list(&self) -> Vec<Escalation> {
+        self.lock().pending.clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Escalation> {
+        self.lock()
+            .pending
+            .iter()
+            .find(|escalation| escalation.id == id)
+            .cloned()
+    }
+
+    /// Keeps reminders in step with an updated notification.
+    pub fn update(&self, app: &AppHandle, id: &str, title: &str, content: &str) {
+        {
+            let mut state = self.lock();
+            let Some(escalation) = state
+                .pending
+                .iter_mut()
+                .find(|escalation| escalation.id == id)
+            else {
+                return;
+            };
+            escalation.title = title.to_owned();
+            escalation.content = content.to_owned();
+        }
+        changed(app);
+    }
+
+    /// Stops escalating a notification, because it was acknowledged or no longer matters.
+    pub fn stop(&self, app: &AppHandle, id: &str) -> Option<Escalation> {
+        let stopped = {
+            let mut state = self.lock();
+            let index = state
+                .pending
+                .iter()
+                .position(|escalation| escalation.id == id)?;
+            state.pending.remove(index)
+        };
+        changed(app);
+        Some(stopped)
+    }
+
+    /// Takes the next step for everything that is due. `held` says which levels Do Not
+    /// Disturb is holding back; those wait until it ends.
+    pub fn take_due(
+        &self,
+        app: &AppHandle,
+        now: u64,
+        held: impl Fn(NotificationLevel) -> bool,
+    ) -> Vec<Step> {
+        let (steps, expired) = {
+            let mut state = self.lock();
+            let before = state.pending.len();
+            let mut steps = Vec::new();
+            state.pending.retain_mut(|escalation| {
+                if escalation.next_at > now || held(escalation.level) {
+                    return true;
+                }
+                if escalation.reminders_sent < escalation.policy.reminders {
+                    escalation.reminders_sent += 1;
+                    escalation.next_at = now + escalation.policy.interval_seconds * 1000;
+                    let gain = (1.0 + GAIN_STEP * escalation.reminders_sent as f32).min(MAX_GAIN);
+                    steps.push(Step::Remind(escalation.clone(), gain));
+                    return true;
+                }
+                // Without a sink there is nothing left to do; the notification stays in the
+                // history unacknowledged.
+                if let Some(sink) = escalation.policy.sink.clone() {
+                    steps.push(Step::HandOff(escalation.clone(), sink));
+                }
+                false
+            });
+            let expired = state.pending.len() < before;
+            (steps, expired)
+        };
+        if !steps.is_empty() || expired {
+            changed(app);
+        }
+        steps
+    }
+}
+
+fn changed(app: &AppHandle) {
+    if let Err(err) = app.emit(ESCALATIONS_CHANGED_EVENT, ()) {
+        eprintln!("Failed to emit {ESCALATIONS_CHANGED_EVENT}: {err}");
+    }
+}
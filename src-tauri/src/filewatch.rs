@@ -0,0 +1,333 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::history::NotificationSource;
+use crate::levels::NotificationLevel;
+use crate::Notification;
+
+pub(crate) const FILE_WATCHES_KEY: &str = "fileWatches";
+// Every watch is polled every couple of seconds; keep the work bounded.
+const MAX_WATCHES: usize = 64;
+// A log that grows faster than this between polls is skipped ahead rather than read in full.
+const MAX_READ_PER_POLL: u64 = 1024 * 1024;
+const MAX_LINE_CHARS: usize = 300;
+const DEFAULT_AGENT: &str = "File watcher";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum WatchKind {
+    /// Fires when the path starts to exist.
+    Appears,
+    /// Fires when a file's size or modification time changes, or a directory's entries do.
+    Changes,
+    /// Fires when a line appended to the file matches `pattern`.
+    Matches { pattern: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FileWatch {
+    /// Assigned when the watch is added.
+    #[serde(default)]
+    pub id: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub kind: WatchKind,
+    #[serde(default)]
+    pub agent: String,
+    /// Defaults to a title describing what happened.
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub level: NotificationLevel,
+    /// Remove the watch after its first notification.
+    #[serde(default = "default_once")]
+    pub once: bool,
+    #[serde(default)]
+    pub source: NotificationSource,
+    /// Unix timestamp in milliseconds.
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+fn default_once() -> bool {
+    true
+}
+
+impl FileWatch {
+    /// Checks a new watch and fills in its id and defaults.
+    pub fn prepare(&mut self, now: u64) -> Result<(), String> {
+        self.path = self.path.trim().to_owned();
+        if !Path::new(&self.path).is_absolute() {
+            return Err(format!("'{}' must be an absolute path", self.path));
+        }
+        if let WatchKind::Matches { pattern } = &self.kind {
+            Regex::new(pattern).map_err(|err| format!("Invalid pattern '{pattern}': {err}"))?;
+        }
+        self.agent = self.agent.trim().to_owned();
+        if self.agent.is_empty() {
+            self.agent = DEFAULT_AGENT.to_owned();
+        }
+        self.title = self
+            .title
+            .take()
+            .map(|title| title.trim().to_owned())
+            .filter(|title| !title.is_empty());
+        self.id = uuid::Uuid::new_v4().to_string();
+        self.created_at = now;
+        Ok(())
+    }
+
+    fn file_name(&self) -> &str {
+        Path::new(&self.path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.path)
+    }
+}
+
+/// Something a watch noticed.
+pub(crate) struct FileEvent {
+    pub watch: FileWatch,
+    detail: String,
+}
+
+impl FileEvent {
+    pub fn notification(&self) -> Notification {
+        let watch = &self.watch;
+        let title = watch.title.clone().unwrap_or_else(|| match &watch.kind {
+            WatchKind::Appears => format!("{} appeared", watch.file_name()),
+            WatchKind::Changes => format!("{} changed", watch.file_name()),
+            WatchKind::Matches { .. } => format!("Match in {}", watch.file_name()),
+        });
+        Notification {
+            title,
+            content: self.detail.clone(),
+            agent: watch.agent.clone(),
+            level: watch.level,
+            silent: false,
+            source: watch.source,
+            url: None,
+            actions: Vec::new(),
+            reply_to: None,
+        }
+    }
+}
+
+/// What a watch saw last time, kept only in memory.
+#[derive(Default)]
+struct Probe {
+    /// False until the first poll, which only records the starting point.
+    primed: bool,
+    present: bool,
+    signature: Option<u64>,
+    /// How far into the file lines have been read.
+    offset: u64,
+    /// The start of a line that has not been finished yet.
+    partial: String,
+    regex: Option<Regex>,
+}
+
+struct WatcherState {
+    watches: Vec<FileWatch>,
+    probes: HashMap<String, Probe>,
+}
+
+/// File and directory watches, polled from the async runtime.
+pub(crate) struct FileWatcher {
+    state: Mutex<WatcherState>,
+}
+
+impl FileWatcher {
+    pub fn new(watches: Vec<FileWatch>) -> Self {
+        Self {
+            state: Mutex::new(WatcherState {
+                watches,
+                probes: HashMap::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, WatcherState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn list(&self) -> Vec<FileWatch> {
+        self.lock().watches.clone()
+    }
+
+    /// Adds a watch already checked by [`FileWatch::prepare`].
+    pub fn add(&self, watch: FileWatch) -> Result<(), String> {
+        let mut state = self.lock();
+        if state.watches.len() >= MAX_WATCHES {
+            return Err(format!("At most {MAX_WATCHES} watches are supported"));
+        }
+        state.watches.push(watch);
+        Ok(())
+    }
+
+    /// Removes a watch; `owner` limits it to watches for that agent.
+    pub fn remove(&self, id: &str, owner: Option<&str>) -> Option<FileWatch> {
+        let mut state = self.lock();
+        let index = state
+            .watches
+            .iter()
+            .position(|watch| watch.id == id && owner.is_none_or(|owner| watch.agent == owner))?;
+        state.probes.remove(id);
+        Some(state.watches.remove(index))
+    }
+
+    /// Checks every watch. Returns what fired and whether one-shot watches were removed, in
+    /// which case the list needs saving. Blocks on the file system.
+    pub fn poll(&self) -> (Vec<FileEvent>, bool) {
+        let mut state = self.lock();
+        let WatcherState { watches, probes } = &mut *state;
+        let mut events = Vec::new();
+        let mut finished = Vec::new();
+        for watch in watches.iter() {
+            let probe = probes.entry(watch.id.clone()).or_default();
+            if let Some(detail) = check(watch, probe) {
+                if watch.once {
+                    finished.push(watch.id.clone());
+                }
+                events.push(FileEvent {
+                    watch: watch.clone(),
+                    detail,
+                });
+            }
+        }
+        watches.retain(|watch| !finished.contains(&watch.id));
+        probes.retain(|id, _| !finished.contains(id));
+        (events, !finished.is_empty())
+    }
+}
+
+/// Looks at one watch and describes what happened since the last look, if anything.
+fn check(watch: &FileWatch, probe: &mut Probe) -> Option<String> {
+    let path = Path::new(&watch.path);
+    let primed = std::mem::replace(&mut probe.primed, true);
+    match &watch.kind {
+        WatchKind::Appears => {
+            let present = path.exists();
+            let was_present = std::mem::replace(&mut probe.present, present);
+            // A one-shot watch also fires for a path that appeared while the app was closed.
+            let fired = if primed {
+                present && !was_present
+            } else {
+                present && watch.once
+            };
+            fired.then(|| format!("{} now exists.", watch.path))
+        }
+        WatchKind::Changes => {
+            let signature = signature(path);
+            let previous = std::mem::replace(&mut probe.signature, signature);
+            if !primed || previous == signature {
+                return None;
+            }
+            Some(match (previous, signature) {
+                (None, Some(_)) => format!("{} was created.", watch.path),
+                (Some(_), None) => format!("{} was removed.", watch.path),
+                _ => format!("{} was modified.", watch.path),
+            })
+        }
+        WatchKind::Matches { pattern } => {
+            if probe.regex.is_none() {
+                probe.regex = Regex::new(pattern).ok();
+            }
+            let lines = read_new_lines(path, probe, primed);
+            let regex = probe.regex.as_ref()?;
+            let mut matching = lines.iter().filter(|line| regex.is_match(line));
+            let first = matching.next()?;
+            let first: String = first.trim().chars().take(MAX_LINE_CHARS).collect();
+            Some(match matching.count() {
+                0 => first,
+                more => format!("{first}\n…and {more} more matching lines"),
+            })
+        }
+    }
+}
+
+/// Size and modification time of a file, or of each entry of a directory, folded into one
+/// number; `None` when the path does not exist.
+fn signature(path: &Path) -> Option<u64> {
+    let metadata = fs::metadata(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    hash_metadata(&metadata, &mut hasher);
+    if metadata.is_dir() {
+        let mut entries: Vec<(String, Option<fs::Metadata>)> = fs::read_dir(path)
+            .ok()?
+            .flatten()
+            .map(|entry| {
+                (
+                    entry.file_name().to_string_lossy().into_owned(),
+                    entry.metadata().ok(),
+                )
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, metadata) in &entries {
+            name.hash(&mut hasher);
+            if let Some(metadata) = metadata {
+                hash_metadata(metadata, &mut hasher);
+            }
+        }
+    }
+    Some(hasher.finish())
+}
+
+fn hash_metadata(metadata: &fs::Metadata, hasher: &mut DefaultHasher) {
+    metadata.len().hash(hasher);
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .hash(hasher);
+}
+
+/// Complete lines appended since the last read. The first read only skips to the end, so
+/// old log lines never fire.
+fn read_new_lines(path: &Path, probe: &mut Probe, primed: bool) -> Vec<String> {
+    let Ok(mut file) = File::open(path) else {
+        // Start from the top if the file comes back, as a rotated log would.
+        probe.offset = 0;
+        probe.partial.clear();
+        return Vec::new();
+    };
+    let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    if !primed {
+        probe.offset = len;
+        return Vec::new();
+    }
+    if len < probe.offset {
+        // Truncated: read it again from the start.
+        probe.offset = 0;
+        probe.partial.clear();
+    }
+    if len - probe.offset > MAX_READ_PER_POLL {
+        probe.offset = len - MAX_READ_PER_POLL;
+        probe.partial.clear();
+    }
+    if len == probe.offset || file.seek(SeekFrom::Start(probe.offset)).is_err() {
+        return Vec::new();
+    }
+    let mut bytes = Vec::new();
+    if let Err(err) = file.take(len - probe.offset).read_to_end(&mut bytes) {
+        eprintln!("Failed to read {}: {err}", path.display());
+        return Vec::new();
+    }
+    probe.offset += bytes.len() as u64;
+
+    let text = std::mem::take(&mut probe.partial) + &String::from_utf8_lossy(&bytes);
+    let mut lines: Vec<String> = text.split('\n').map(str::to_owned).collect();
+    // Whatever follows the last newline is still being written.
+    probe.partial = lines.pop().unwrap_or_default();
+    lines
+}
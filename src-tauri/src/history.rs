@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
+
+use crate::{levels::NotificationLevel, rules::MatchedRule};
+
+// History lives in its own store file so it never bloats the settings store.
+const HISTORY_STORE_FILE: &str = "history.json";
+const HISTORY_KEY: &str = "notifications";
+// The whole list is rewritten on every save; keep it bounded.
+const MAX_HISTORY_ENTRIES: usize = 1000;
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 200;
+// Changes within this long of each other are saved together.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum NotificationSource {
+    #[default]
+    Http,
+    Mcp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum DeliveryOutcome {
+    Delivered,
+    /// Held for the Do-Not-Disturb digest.
+    Queued,
+    /// Dropped by a notification rule.
+    Muted,
+    /// Dropped as a repeat of a notification shown moments before.
+    Duplicate,
+    /// Shown as part of a burst summary.
+    Merged,
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct NotificationRecord {
+    pub id: String,
+    /// Unix timestamp in milliseconds.
+    pub timestamp: u64,
+    pub title: String,
+    pub content: String,
+    pub agent: String,
+    // Records written before levels existed read back as info.
+    #[serde(default)]
+    pub level: NotificationLevel,
+    pub source: NotificationSource,
+    pub outcome: DeliveryOutcome,
+    /// The notification rule that applied, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<MatchedRule>,
+    /// When the title, content or level was last replaced, unix milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
+    /// Dismissed by the agent; it can no longer be updated.
+    #[serde(default)]
+    pub dismissed: bool,
+    /// When the user acknowledged it, unix milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledged_at: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct HistoryQuery {
+    pub agent: Option<String>,
+    pub level: Option<NotificationLevel>,
+    /// Inclusive lower bound, unix milliseconds.
+    pub since: Option<u64>,
+    /// Inclusive upper bound, unix milliseconds.
+    pub until: Option<u64>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+    /// Set from the caller's token, never from the query string; limits results to
+    /// notifications sent under that token.
+    #[serde(skip)]
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct HistoryPage {
+    pub items: Vec<NotificationRecord>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+struct HistoryFile {
+    // Oldest first so trimming is a cheap pop from the front.
+    records: Mutex<VecDeque<NotificationRecord>>,
+    // Held for a whole save, so a slow save cannot land after a newer one.
+    saving: Mutex<()>,
+}
+
+impl HistoryFile {
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<NotificationRecord>> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Writes the records as they are now. History is best-effort; a failed write must never
+    /// block delivery.
+    fn save(&self, app: &tauri::AppHandle) {
+        let _saving = self.saving.lock().unwrap_or_else(|e| e.into_inner());
+        let snapshot = serde_json::to_value(&*self.lock());
+        match snapshot {
+            Ok(snapshot) => {
+                if let Err(err) = persist_history(app, snapshot) {
+                    eprintln!("{err}");
+                }
+            }
+            Err(err) => eprintln!("Failed to serialize notification history: {err}"),
+        }
+    }
+}
+
+pub(crate) struct NotificationHistory {
+    app: tauri::AppHandle,
+    file: Arc<HistoryFile>,
+    // Tells the writer thread that the records changed.
+    writer: mpsc::Sender<()>,
+}
+
+impl NotificationHistory {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let records = match app.store(HISTORY_STORE_FILE) {
+            Ok(store) => match store.get(HISTORY_KEY) {
+                Some(value) => serde_json::from_value::<VecDeque<NotificationRecord>>(value)
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to parse stored notification history: {err}");
+                        VecDeque::new()
+                    }),
+                None => VecDeque::new(),
+            },
+            Err(err) => {
+                eprintln!("Failed to open history store: {err}");
+                VecDeque::new()
+            }
+        };
+
+        let file = Arc::new(HistoryFile {
+            records: Mutex::new(records),
+            saving: Mutex::new(()),
+        });
+        let (writer, changes) = mpsc::channel();
+        // Saving rewrites the whole file, so it happens on its own thread rather than on
+        // whichever async worker dispatched the notification.
+        let spawned = thread::Builder::new().name("history-writer".into()).spawn({
+            let app = app.clone();
+            let file = file.clone();
+            move || run_history_writer(app, file, changes)
+        });
+        if let Err(err) = spawned {
+            eprintln!("Failed to start history writer: {err}");
+        }
+
+        Self {
+            app: app.clone(),
+            file,
+            writer,
+        }
+    }
+
+    pub fn record(&self, record: NotificationRecord) {
+        {
+            let mut records = self.file.lock();
+            records.push_back(record);
+            while records.len() > MAX_HISTORY_ENTRIES {
+                records.pop_front();
+            }
+        }
+        self.changed();
+    }
+
+    pub fn get(&self, id: &str) -> Option<NotificationRecord> {
+        self.file
+            .lock()
+            .iter()
+            .rev()
+            .find(|record| record.id == id)
+            .cloned()
+    }
+
+    /// Changes a stored record in place and returns the result, or `None` if it is gone.
+    pub fn update(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut NotificationRecord),
+    ) -> Option<NotificationRecord> {
+        let updated = {
+            let mut records = self.file.lock();
+            let record = records.iter_mut().rev().find(|record| record.id == id)?;
+            change(record);
+            record.clone()
+        };
+        self.changed();
+        Some(updated)
+    }
+
+    pub fn query(&self, query: &HistoryQuery) -> HistoryPage {
+        let agent = query
+            .agent
+            .as_deref()
+            .map(str::trim)
+            .filter(|agent| !agent.is_empty());
+        let offset = query.offset.unwrap_or(0);
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+
+        let records = self.file.lock();
+        let matching: Vec<&NotificationRecord> = records
+            .iter()
+            .rev()
+            .filter(|record| agent.is_none_or(|agent| record.agent == agent))
+            .filter(|record| {
+                query
+                    .owner
+                    .as_deref()
+                    .is_none_or(|owner| record.agent == owner)
+            })
+            .filter(|record| query.level.is_none_or(|level| record.level == level))
+            .filter(|record| query.since.is_none_or(|since| record.timestamp >= since))
+            .filter(|record| query.until.is_none_or(|until| record.timestamp <= until))
+            .collect();
+
+        HistoryPage {
+            total: matching.len(),
+            items: matching
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+            offset,
+            limit,
+        }
+    }
+
+    pub fn clear(&self) {
+        self.file.lock().clear();
+        self.changed();
+    }
+
+    /// Saves right away instead of waiting for the writer, e.g. before quitting.
+    pub fn flush(&self) {
+        self.file.save(&self.app);
+    }
+
+    fn changed(&self) {
+        if self.writer.send(()).is_err() {
+            eprintln!("History writer is not running");
+        }
+    }
+}
+
+/// Saves the history shortly after it changes, once for any number of changes in between.
+fn run_history_writer(app: tauri::AppHandle, file: Arc<HistoryFile>, changes: mpsc::Receiver<()>) {
+    while changes.recv().is_ok() {
+        thread::sleep(SAVE_DELAY);
+        while changes.try_recv().is_ok() {}
+        file.save(&app);
+    }
+}
+
+fn persist_history(app: &tauri::AppHandle, value: serde_json::Value) -> Result<(), String> {
+    let store = app
+        .store(HISTORY_STORE_FILE)
+        .map_err(|err| format!("Failed to open history store: {err}"))?;
+    store.set(HISTORY_KEY, value);
+    store
+        .save()
+        .map_err(|err| format!("Failed to save notification history: {err}"))
+}
@@ -0,0 +1,284 @@
+use serde_json::{json, Value};
+
+pub(crate) const PARSE_ERROR: i64 = -32700;
+pub(crate) const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+// Implementation-defined server errors live in -32000..=-32099.
+pub(crate) const SERVER_ERROR: i64 = -32000;
+pub(crate) const RATE_LIMITED: i64 = -32029;
+
+/// A single JSON-RPC message sent by the client, classified by shape.
+#[derive(Debug)]
+pub(crate) enum Message {
+    Request {
+        id: Value,
+        method: String,
+        params: Option<Value>,
+    },
+    Notification,
+    /// A reply to a request the server sent; nothing to answer.
+    Response,
+    /// Something that is JSON but not a valid message; answered with `-32600`.
+    Invalid {
+        id: Value,
+        reason: &'static str,
+    },
+}
+
+#[derive(Debug)]
+pub(crate) enum Payload {
+    Single(Message),
+    Batch(Vec<Message>),
+}
+
+#[derive(Debug)]
+pub(crate) struct RpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn invalid_params(message: impl std::fmt::Display) -> Self {
+        Self::new(INVALID_PARAMS, format!("Invalid params: {message}"))
+    }
+
+    pub fn method_not_found() -> Self {
+        Self::new(METHOD_NOT_FOUND, "Method not found")
+    }
+
+    pub fn into_response(self, id: Value) -> Value {
+        let mut response = jsonrpc_error(Some(id), self.code, &self.message);
+        if let Some(data) = self.data {
+            response["error"]["data"] = data;
+        }
+        response
+    }
+}
+
+pub(crate) fn jsonrpc_success(id: Value, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result
+    })
+}
+
+pub(crate) fn jsonrpc_error(id: Option<Value>, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id.unwrap_or(Value::Null),
+        "error": { "code": code, "message": message }
+    })
+}
+
+/// Parses a request body into one message or a batch.
+///
+/// Bodies that are not JSON at all produce the ready-to-send `-32700` error, since no id can be
+/// recovered. Individual malformed messages become [`Message::Invalid`] so the rest of a batch
+/// is still processed.
+pub(crate) fn parse(body: &[u8]) -> Result<Payload, Value> {
+    let value: Value = serde_json::from_slice(body)
+        .map_err(|err| jsonrpc_error(None, PARSE_ERROR, &format!("Parse error: {err}")))?;
+
+    match value {
+        Value::Array(items) if items.is_empty() => Err(jsonrpc_error(
+            None,
+            INVALID_REQUEST,
+            "Invalid Request: empty batch",
+        )),
+        Value::Array(items) => Ok(Payload::Batch(items.into_iter().map(classify).collect())),
+        value => Ok(Payload::Single(classify(value))),
+    }
+}
+
+fn classify(value: Value) -> Message {
+    let Value::Object(mut object) = value else {
+        return Message::Invalid {
+            id: Value::Null,
+            reason: "Invalid Request: expected an object",
+        };
+    };
+
+    // Ids must be strings or numbers; anything else cannot be echoed back safely.
+    let id = match object.get("id") {
+        None => None,
+        // Error replies to our own requests may carry a null id when the client could not parse them.
+        Some(Value::Null) if object.contains_key("error") => return Message::Response,
+        Some(id @ (Value::String(_) | Value::Number(_))) => Some(id.clone()),
+        Some(_) => {
+            return Message::Invalid {
+                id: Value::Null,
+                reason: "Invalid Request: id must be a string or number",
+            }
+        }
+    };
+
+    if object.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Message::Invalid {
+            id: id.unwrap_or(Value::Null),
+            reason: "Invalid Request: jsonrpc must be \"2.0\"",
+        };
+    }
+
+    match (object.remove("method"), id) {
+        (Some(Value::String(method)), Some(id)) => Message::Request {
+            id,
+            method,
+            params: object.remove("params"),
+        },
+        (Some(Value::String(_)), None) => Message::Notification,
+        (Some(_), id) => Message::Invalid {
+            id: id.unwrap_or(Value::Null),
+            reason: "Invalid Request: method must be a string",
+        },
+        (None, Some(_)) if object.contains_key("result") || object.contains_key("error") => {
+            Message::Response
+        }
+        (None, id) => Message::Invalid {
+            id: id.unwrap_or(Value::Null),
+            reason: "Invalid Request: missing method",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single(body: &str) -> Message {
+        match parse(body.as_bytes()) {
+            Ok(Payload::Single(message)) => message,
+            other => panic!("expected a single message, got {other:?}"),
+        }
+    }
+
+    fn invalid(message: Message) -> (Value, &'static str) {
+        match message {
+            Message::Invalid { id, reason } => (id, reason),
+            other => panic!("expected an invalid message, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn body_that_is_not_json_is_a_parse_error_with_a_null_id() {
+        let err = parse(b"{\"jsonrpc\": \"2.0\",").unwrap_err();
+        assert_eq!(err["error"]["code"], PARSE_ERROR);
+        assert_eq!(err["id"], Value::Null);
+    }
+
+    #[test]
+    fn empty_batch_is_an_invalid_request() {
+        let err = parse(b"[]").unwrap_err();
+        assert_eq!(err["error"]["code"], INVALID_REQUEST);
+        assert_eq!(err["id"], Value::Null);
+    }
+
+    #[test]
+    fn classifies_requests_notifications_and_responses() {
+        match single(r#"{"jsonrpc": "2.0", "id": 7, "method": "ping", "params": {"a": 1}}"#) {
+            Message::Request { id, method, params } => {
+                assert_eq!(id, json!(7));
+                assert_eq!(method, "ping");
+                assert_eq!(params, Some(json!({"a": 1})));
+            }
+            other => panic!("expected a request, got {other:?}"),
+        }
+        assert!(matches!(
+            single(r#"{"jsonrpc": "2.0", "id": "a", "method": "ping"}"#),
+            Message::Request { params: None, .. }
+        ));
+        assert!(matches!(
+            single(r#"{"jsonrpc": "2.0", "method": "notifications/initialized"}"#),
+            Message::Notification
+        ));
+        assert!(matches!(
+            single(r#"{"jsonrpc": "2.0", "id": 1, "result": {}}"#),
+            Message::Response
+        ));
+        assert!(matches!(
+            single(r#"{"jsonrpc": "2.0", "id": null, "error": {"code": -32700}}"#),
+            Message::Response
+        ));
+    }
+
+    #[test]
+    fn invalid_messages_echo_the_id_only_when_it_is_usable() {
+        assert_eq!(invalid(single("42")).0, Value::Null);
+        assert_eq!(
+            invalid(single(
+                r#"{"jsonrpc": "2.0", "id": true, "method": "ping"}"#
+            ))
+            .0,
+            Value::Null
+        );
+        assert_eq!(
+            invalid(single(
+                r#"{"jsonrpc": "2.0", "id": null, "method": "ping"}"#
+            ))
+            .0,
+            Value::Null
+        );
+        assert_eq!(
+            invalid(single(r#"{"jsonrpc": "1.0", "id": 3, "method": "ping"}"#)),
+            (json!(3), "Invalid Request: jsonrpc must be \"2.0\"")
+        );
+        assert_eq!(
+            invalid(single(r#"{"jsonrpc": "2.0", "id": "x", "method": 5}"#)),
+            (json!("x"), "Invalid Request: method must be a string")
+        );
+        assert_eq!(
+            invalid(single(r#"{"jsonrpc": "2.0", "id": 4}"#)),
+            (json!(4), "Invalid Request: missing method")
+        );
+    }
+
+    #[test]
+    fn batches_keep_valid_messages_next_to_invalid_ones() {
+        let body = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+            1,
+            {"jsonrpc": "2.0", "method": "notifications/initialized"}
+        ]"#;
+        let Ok(Payload::Batch(messages)) = parse(body.as_bytes()) else {
+            panic!("expected a batch");
+        };
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[0], Message::Request { .. }));
+        assert!(matches!(messages[1], Message::Invalid { .. }));
+        assert!(matches!(messages[2], Message::Notification));
+    }
+
+    #[test]
+    fn errors_carry_their_data() {
+        let response = RpcError::new(RATE_LIMITED, "Slow down")
+            .with_data(json!({"retry_after": 3}))
+            .into_response(json!(9));
+        assert_eq!(
+            response,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 9,
+                "error": { "code": RATE_LIMITED, "message": "Slow down", "data": {"retry_after": 3} }
+            })
+        );
+        assert_eq!(
+            RpcError::method_not_found().into_response(json!(1))["error"]["code"],
+            METHOD_NOT_FOUND
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::audio;
+
+pub(crate) const LEVELS_SETTINGS_KEY: &str = "notificationLevels";
+
+/// How serious a notification is; picks the sound, urgency and title prefix it gets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NotificationLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl NotificationLevel {
+    pub const NAMES: [&'static str; 5] = ["info", "success", "warning", "error", "critical"];
+
+    pub fn as_str(self) -> &'static str {
+        Self::NAMES[self as usize]
+    }
+}
+
+/// How hard a level should try to get the user's attention.
+///
+/// The desktop notification APIs we use have no portable urgency flag, so this is enforced by
+/// the app itself rather than handed to the OS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Urgency {
+    /// Shown without a sound.
+    Low,
+    #[default]
+    Normal,
+    /// Gets through Do-Not-Disturb and is never held back for a burst summary.
+    Critical,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct LevelStyle {
+    /// Prepended to the title, e.g. an emoji.
+    #[serde(default)]
+    pub title_prefix: String,
+    /// Icon name or path for the desktop notification; the app icon when unset.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Bundled sound name or audio file path; the default sound when unset.
+    #[serde(default)]
+    pub sound: Option<String>,
+    #[serde(default)]
+    pub urgency: Urgency,
+}
+
+impl LevelStyle {
+    pub fn plays_sound(&self) -> bool {
+        self.urgency != Urgency::Low
+    }
+
+    fn new(title_prefix: &str, sound: Option<&str>, urgency: Urgency) -> Self {
+        Self {
+            title_prefix: title_prefix.to_owned(),
+            icon: None,
+            sound: sound.map(str::to_owned),
+            urgency,
+        }
+    }
+}
+
+/// Per-level presentation, stored under [`LEVELS_SETTINGS_KEY`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct LevelSettings {
+    pub info: LevelStyle,
+    pub success: LevelStyle,
+    pub warning: LevelStyle,
+    pub error: LevelStyle,
+    pub critical: LevelStyle,
+}
+
+impl Default for LevelSettings {
+    fn default() -> Self {
+        Self {
+            info: LevelStyle::new("", None, Urgency::Normal),
+            success: LevelStyle::new("✅ ", Some("Glass"), Urgency::Normal),
+            warning: LevelStyle::new("⚠️ ", Some("Funk"), Urgency::Normal),
+            error: LevelStyle::new("❌ ", Some("Basso"), Urgency::Normal),
+            critical: LevelStyle::new("🚨 ", Some("Sosumi"), Urgency::Critical),
+        }
+    }
+}
+
+impl LevelSettings {
+    pub fn style(&self, level: NotificationLevel) -> &LevelStyle {
+        match level {
+            NotificationLevel::Info => &self.info,
+            NotificationLevel::Success => &self.success,
+            NotificationLevel::Warning => &self.warning,
+            NotificationLevel::Error => &self.error,
+            NotificationLevel::Critical => &self.critical,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, style) in NotificationLevel::NAMES.iter().zip(self.styles()) {
+            if let Some(sound) = &style.sound {
+                audio::decode_sound(sound).map_err(|err| format!("Sound for '{name}': {err}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every sound the levels refer to.
+    pub fn sounds(&self) -> impl Iterator<Item = &str> {
+        self.styles()
+            .into_iter()
+            .filter_map(|style| style.sound.as_deref())
+    }
+
+    fn styles(&self) -> [&LevelStyle; 5] {
+        [
+            &self.info,
+            &self.success,
+            &self.warning,
+            &self.error,
+            &self.critical,
+        ]
+    }
+}
@@ -1,46 +1,88 @@
+mod actions;
+mod audio;
+mod auth;
+mod coalesce;
+mod escalation;
+mod filewatch;
+mod history;
+mod jsonrpc;
+mod levels;
+mod mcp;
+mod origin;
+mod probes;
+mod procwatch;
+mod prompts;
+mod protocol;
+mod quiet;
+mod ratelimit;
+mod rules;
+mod schedule;
+mod session;
+mod sinks;
+mod tasks;
+
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
 };
 
-use std::convert::Infallible;
-use std::io::BufReader;
-use std::time::Duration;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use actions::{
+    ActionKind, ActionRegistry, ActionResult, ActionableNotification, NotificationAction,
+};
+use audio::{AudioPlayer, AudioSettings, DecodedSound, SoundBank};
+use auth::{AgentPolicy, ApiTokenSummary, AuthSettings, CreatedApiToken, TokenIdentity};
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
-    response::{
-        sse::{Event, KeepAlive, Sse},
-        IntoResponse,
-    },
-    routing::post,
-    Json, Router,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, patch, post},
+    Extension, Json, Router,
 };
+use coalesce::{CoalesceSettings, Coalescer};
 #[cfg(target_os = "macos")]
 use cocoa::appkit::{NSColor, NSWindow};
 #[cfg(target_os = "macos")]
 use cocoa::base::{id, nil, YES};
-use rodio::{Decoder, OutputStream, Sink};
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use escalation::{Escalation, EscalationSettings, Escalations, Step};
+use filewatch::{FileWatch, FileWatcher};
+use history::{
+    DeliveryOutcome, HistoryPage, HistoryQuery, NotificationHistory, NotificationRecord,
+    NotificationSource,
+};
+use levels::{LevelSettings, NotificationLevel, Urgency};
+use origin::RequestOriginPolicy;
+use probes::{Probe, ProbeRegistry, ProbeRequest, ProbeTarget, Progress};
+use procwatch::{ProcessWatch, ProcessWatchRequest, ProcessWatcher};
+use prompts::{McpToolSettings, PendingPrompt, PromptAnswer, PromptRegistry};
+use quiet::{QuietHours, QuietSettings, QuietStatus};
+use ratelimit::{RateLimitSettings, RateLimited, RateLimiter};
+use rules::{MatchedRule, RuleSet, RuleSettings};
+use schedule::{ScheduledNotification, Scheduler};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use session::{McpSession, SessionRegistry};
+use tasks::{FinishedTask, RunningTask, TaskOutcome, TaskTracker};
 #[cfg(desktop)]
-use tauri::{image::Image, menu::MenuBuilder, menu::MenuItem, tray::TrayIconBuilder};
+use tauri::{
+    image::Image,
+    menu::{CheckMenuItem, MenuBuilder, MenuItem, Submenu},
+    tray::TrayIconBuilder,
+    Emitter,
+};
 use tauri::{
     plugin::PermissionState, webview::Color, Manager, TitleBarStyle, WebviewUrl,
     WebviewWindowBuilder, WindowEvent,
 };
 use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_store::StoreExt;
 use tokio::sync::{Mutex, RwLock};
-use tokio::{task, time};
-use tokio_stream::{wrappers::IntervalStream, StreamExt};
-
-// Keep the default notification sound embedded so it ships with the app.
-const DEFAULT_SOUND: &[u8] = include_bytes!("../sounds/Ping.wav");
-const DISABLE_SOUND_ENV: &str = "AGENT_NOTIFIER_DISABLE_SOUND";
-// MCP HTTP Stream transport as of the 2025-11-25 specification.
-const MCP_PROTOCOL_VERSION: &str = "2025-11-25";
+use tokio::task;
+
 const STORE_FILE: &str = "settings.json";
 const HTTP_SETTINGS_KEY: &str = "httpBindings";
 // Theme background: oklch(0.1649 0.0352 281.8285) ≈ #0c0c1d.
@@ -74,191 +116,2190 @@ async fn save_http_bindings(
     restart_http_server(&app, &state).await
 }
 
-#[derive(Clone)]
-struct AppState {
+#[tauri::command]
+async fn get_notification_history(
+    query: Option<HistoryQuery>,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<HistoryPage, String> {
+    Ok(state.history.query(&query.unwrap_or_default()))
+}
+
+#[tauri::command]
+async fn clear_notification_history(state: tauri::State<'_, ManagedState>) -> Result<(), String> {
+    state.history.clear();
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_api_tokens(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<Vec<ApiTokenSummary>, String> {
+    let auth = state.auth.read().await;
+    Ok(auth.tokens.iter().map(ApiTokenSummary::from).collect())
+}
+
+#[tauri::command]
+async fn create_api_token(
+    label: String,
+    agent_policy: Option<AgentPolicy>,
     app: tauri::AppHandle,
-    listening: Arc<AtomicBool>,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<CreatedApiToken, String> {
+    let mut auth = state.auth.write().await;
+    let mut updated = auth.clone();
+    let created = auth::create_token(&mut updated, &label, agent_policy.unwrap_or_default())?;
+    persist_stored_setting(&app, auth::AUTH_SETTINGS_KEY, &updated, "API tokens")?;
+    *auth = updated;
+    Ok(created)
 }
 
-#[derive(Deserialize)]
-struct NotifyRequest {
-    title: String,
-    content: String,
-    agent: String,
+#[tauri::command]
+async fn revoke_api_token(
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    let mut auth = state.auth.write().await;
+    let mut updated = auth.clone();
+    auth::revoke_token(&mut updated, &id)?;
+    persist_stored_setting(&app, auth::AUTH_SETTINGS_KEY, &updated, "API tokens")?;
+    *auth = updated;
+    Ok(())
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct HttpSettings {
-    bind_address: String,
-    port: u16,
+#[tauri::command]
+async fn set_token_auth_required(
+    required: bool,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    let mut auth = state.auth.write().await;
+    if required && auth.tokens.is_empty() {
+        return Err("Create a token before requiring authentication".into());
+    }
+    let mut updated = auth.clone();
+    updated.require_token = required;
+    persist_stored_setting(&app, auth::AUTH_SETTINGS_KEY, &updated, "API tokens")?;
+    *auth = updated;
+    Ok(())
 }
 
-impl Default for HttpSettings {
-    fn default() -> Self {
-        Self {
-            bind_address: "127.0.0.1".into(),
-            port: 60766,
-        }
+#[tauri::command]
+async fn list_mcp_sessions(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<Vec<McpSession>, String> {
+    Ok(state.sessions.list().await)
+}
+
+#[tauri::command]
+async fn list_pending_prompts(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<Vec<PendingPrompt>, String> {
+    Ok(state.prompts.list())
+}
+
+#[tauri::command]
+async fn answer_prompt(
+    id: String,
+    answer: PromptAnswer,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    state.prompts.answer(&id, answer)
+}
+
+#[tauri::command]
+async fn get_mcp_tool_settings(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<McpToolSettings, String> {
+    Ok(state.mcp_tools.read().await.clone())
+}
+
+#[tauri::command]
+async fn save_mcp_tool_settings(
+    settings: McpToolSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    persist_stored_setting(
+        &app,
+        prompts::MCP_SETTINGS_KEY,
+        &settings,
+        "MCP tool settings",
+    )?;
+    let changed = {
+        let mut guard = state.mcp_tools.write().await;
+        let changed = guard.ask_user_enabled != settings.ask_user_enabled;
+        *guard = settings;
+        changed
+    };
+
+    if changed {
+        state
+            .sessions
+            .broadcast("notifications/tools/list_changed", json!({}))
+            .await;
     }
+    Ok(())
 }
 
-struct ManagedState {
-    listening: Arc<AtomicBool>,
-    server_task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
-    settings: RwLock<HttpSettings>,
+#[tauri::command]
+async fn get_level_settings(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<LevelSettings, String> {
+    Ok(state.levels.read().await.clone())
 }
 
-// Windows toast text blocks cap at 1024 chars; keep a conservative ceiling to avoid truncation.
-const MAX_NOTIFICATION_BODY_CHARS: usize = 1000;
-// Soft limit to match the SKILL guidance; keeps space for the agent prefix.
-const SOFT_CONTENT_LIMIT_CHARS: usize = 950;
+#[tauri::command]
+async fn save_level_settings(
+    settings: LevelSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    let settings = task::spawn_blocking(move || settings.validate().map(|()| settings))
+        .await
+        .map_err(|err| format!("Failed to check sounds: {err}"))??;
+    persist_stored_setting(
+        &app,
+        levels::LEVELS_SETTINGS_KEY,
+        &settings,
+        "notification levels",
+    )?;
 
-fn play_notification_sound() {
-    // Allow opting out (useful for CI or silent environments).
-    if std::env::var_os(DISABLE_SOUND_ENV).is_some() {
-        return;
+    let audio_settings = state.sounds.read().await.settings().clone();
+    let bank = load_sound_bank(audio_settings, settings.clone()).await?;
+    *state.levels.write().await = settings;
+    *state.sounds.write().await = bank;
+    Ok(())
+}
+
+#[tauri::command]
+fn list_bundled_sounds() -> Vec<&'static str> {
+    audio::bundled_sound_names()
+}
+
+#[tauri::command]
+async fn get_audio_settings(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<AudioSettings, String> {
+    Ok(state.sounds.read().await.settings().clone())
+}
+
+#[tauri::command]
+async fn save_audio_settings(
+    settings: AudioSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    let settings = task::spawn_blocking(move || settings.validate().map(|()| settings))
+        .await
+        .map_err(|err| format!("Failed to check sounds: {err}"))??;
+    persist_stored_setting(&app, audio::AUDIO_SETTINGS_KEY, &settings, "audio settings")?;
+
+    state.player.configure(&settings);
+    #[cfg(desktop)]
+    sync_tray_mute(&app, settings.muted);
+    let levels = state.levels.read().await.clone();
+    let bank = load_sound_bank(settings, levels).await?;
+    *state.sounds.write().await = bank;
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_output_devices() -> Result<Vec<String>, String> {
+    task::spawn_blocking(audio::output_device_names)
+        .await
+        .map_err(|err| format!("Failed to list output devices: {err}"))
+}
+
+/// Mutes or unmutes notification sounds from the tray, keeping the store and the settings
+/// window in step.
+#[cfg(desktop)]
+async fn set_sounds_muted(app: &tauri::AppHandle, muted: bool) -> Result<(), String> {
+    let state = app.state::<ManagedState>();
+    let settings = {
+        let mut sounds = state.sounds.write().await;
+        sounds.set_muted(muted);
+        sounds.settings().clone()
+    };
+    if let Err(err) = app.emit(audio::MUTE_CHANGED_EVENT, muted) {
+        eprintln!("Failed to emit {}: {err}", audio::MUTE_CHANGED_EVENT);
     }
+    persist_stored_setting(app, audio::AUDIO_SETTINGS_KEY, &settings, "audio settings")
+}
 
-    // Spawn onto Tokio so we don't block the HTTP handler while audio initializes/plays.
-    task::spawn_blocking(|| {
-        let Ok((stream, handle)) = OutputStream::try_default() else {
-            eprintln!("Audio output init failed");
-            return;
-        };
+#[tauri::command]
+async fn preview_sound(sound: String, state: tauri::State<'_, ManagedState>) -> Result<(), String> {
+    let decoded = task::spawn_blocking(move || audio::decode_sound(&sound))
+        .await
+        .map_err(|err| format!("Failed to load sound: {err}"))??;
+    state.player.play(decoded);
+    Ok(())
+}
 
-        let cursor = std::io::Cursor::new(DEFAULT_SOUND);
-        let reader = BufReader::new(cursor);
-        let decoder = match Decoder::new(reader) {
-            Ok(decoder) => decoder,
-            Err(err) => {
-                eprintln!("Failed to decode notification sound: {err}");
-                return;
-            }
-        };
+// Decoding reads files from disk, so keep it off the async workers.
+async fn load_sound_bank(
+    settings: AudioSettings,
+    levels: LevelSettings,
+) -> Result<SoundBank, String> {
+    task::spawn_blocking(move || SoundBank::load(settings, &levels))
+        .await
+        .map_err(|err| format!("Failed to load sounds: {err}"))
+}
 
-        let sink = match Sink::try_new(&handle) {
-            Ok(sink) => sink,
-            Err(err) => {
-                eprintln!("Failed to create audio sink: {err}");
-                return;
-            }
-        };
+#[tauri::command]
+async fn get_quiet_settings(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<QuietSettings, String> {
+    Ok(state.quiet.settings())
+}
 
-        sink.append(decoder);
-        // Block this worker thread until playback finishes so the stream stays alive.
-        sink.sleep_until_end();
+#[tauri::command]
+async fn save_quiet_settings(
+    settings: QuietSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    persist_stored_setting(&app, quiet::QUIET_SETTINGS_KEY, &settings, "quiet hours")?;
+    state.quiet.set_settings(settings);
+    // Removing the schedule that was in effect should release the queue right away.
+    deliver_quiet_digest(&state.app_state(&app)).await;
+    Ok(())
+}
 
-        // Keep the stream alive until sink finishes.
-        drop(stream);
-    });
+#[tauri::command]
+async fn get_quiet_status(state: tauri::State<'_, ManagedState>) -> Result<QuietStatus, String> {
+    Ok(state.quiet.status())
 }
 
-fn ensure_notification_permission(app: &tauri::AppHandle) {
-    // Best-effort permission check and request so macOS users get the system prompt up front.
-    match app.notification().permission_state() {
-        Ok(PermissionState::Granted) => {}
-        Ok(PermissionState::Prompt | PermissionState::PromptWithRationale) => {
-            if let Err(err) = app.notification().request_permission() {
-                eprintln!("Notification permission request failed: {err}");
-            }
+#[tauri::command]
+async fn pause_notifications(
+    minutes: u64,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<QuietStatus, String> {
+    if !(1..=MAX_PAUSE_MINUTES).contains(&minutes) {
+        return Err(format!(
+            "Pause must be between 1 and {MAX_PAUSE_MINUTES} minutes"
+        ));
+    }
+    state.quiet.pause(unix_millis_now() + minutes * 60 * 1000);
+    Ok(state.quiet.status())
+}
+
+#[tauri::command]
+async fn resume_notifications(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<QuietStatus, String> {
+    state.quiet.resume();
+    deliver_quiet_digest(&state.app_state(&app)).await;
+    Ok(state.quiet.status())
+}
+
+#[tauri::command]
+async fn get_notification_rules(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<RuleSettings, String> {
+    Ok(state.rules.read().await.settings().clone())
+}
+
+#[tauri::command]
+async fn save_notification_rules(
+    mut settings: RuleSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<RuleSettings, String> {
+    for rule in &mut settings.rules {
+        if rule.id.is_empty() {
+            rule.id = uuid::Uuid::new_v4().to_string();
         }
-        Ok(PermissionState::Denied) => {
-            eprintln!("Notification permission is denied for this app.");
+    }
+    let persisted = settings.clone();
+    let rule_set = task::spawn_blocking(move || RuleSet::compile(settings))
+        .await
+        .map_err(|err| format!("Failed to check rules: {err}"))??;
+    persist_stored_setting(
+        &app,
+        rules::RULES_SETTINGS_KEY,
+        &persisted,
+        "notification rules",
+    )?;
+    *state.rules.write().await = rule_set;
+    Ok(persisted)
+}
+
+#[tauri::command]
+async fn get_coalesce_settings(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<CoalesceSettings, String> {
+    Ok(state.coalescer.settings())
+}
+
+#[tauri::command]
+async fn save_coalesce_settings(
+    settings: CoalesceSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    persist_stored_setting(
+        &app,
+        coalesce::COALESCE_SETTINGS_KEY,
+        &settings,
+        "notification coalescing",
+    )?;
+    state.coalescer.set_settings(settings);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_rate_limit_settings(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<RateLimitSettings, String> {
+    Ok(state.limiter.settings())
+}
+
+#[tauri::command]
+async fn save_rate_limit_settings(
+    settings: RateLimitSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    settings.validate()?;
+    persist_stored_setting(
+        &app,
+        ratelimit::RATE_LIMIT_SETTINGS_KEY,
+        &settings,
+        "rate limits",
+    )?;
+    state.limiter.set_settings(settings);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_escalation_settings(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<EscalationSettings, String> {
+    Ok(state.escalations.settings())
+}
+
+#[tauri::command]
+async fn save_escalation_settings(
+    mut settings: EscalationSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<EscalationSettings, String> {
+    let settings = task::spawn_blocking(move || settings.validate().map(|()| settings))
+        .await
+        .map_err(|err| format!("Failed to check escalation policies: {err}"))??;
+    persist_stored_setting(
+        &app,
+        escalation::ESCALATION_SETTINGS_KEY,
+        &settings,
+        "escalation policies",
+    )?;
+    state.escalations.set_settings(settings.clone());
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn list_escalations(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<Vec<Escalation>, String> {
+    Ok(state.escalations.list())
+}
+
+#[tauri::command]
+async fn acknowledge_notification(
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    acknowledge(&state.app_state(&app), &id, None).map_err(|err| err.message())
+}
+
+#[tauri::command]
+async fn list_notification_actions(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<Vec<ActionableNotification>, String> {
+    Ok(state.actions.list())
+}
+
+/// Runs an action the user picked in the window, or opens the notification's link when
+/// `action_id` is missing, then tells the agent.
+#[tauri::command]
+async fn run_notification_action(
+    id: String,
+    action_id: Option<String>,
+    text: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<ActionResult, String> {
+    let invoked = state
+        .actions
+        .invoke(&app, &id, action_id.as_deref(), text)?;
+
+    // Copying happens in the window, which already has the text.
+    let opened = match (&invoked.kind, &invoked.url) {
+        (Some(ActionKind::OpenUrl { url }), _) | (None, Some(url)) => {
+            app.opener().open_url(url, None::<&str>)
+        }
+        // Agents pick the label, so paths are only shown in the file manager, never launched;
+        // otherwise a button called "Open report" could run an app or script.
+        (Some(ActionKind::OpenPath { path }), _) => app.opener().reveal_item_in_dir(path),
+        _ => Ok(()),
+    };
+    opened.map_err(|err| format!("Failed to open: {err}"))?;
+    // Acting on a notification shows it was seen.
+    if let Err(err) = acknowledge(&state.app_state(&app), &id, None) {
+        eprintln!("{}", err.message());
+    }
+
+    if let Some(session) = &invoked.session {
+        state
+            .sessions
+            .notify(
+                session,
+                actions::ACTION_RESULT_METHOD,
+                json!({ "notificationId": id, "result": invoked.result }),
+            )
+            .await;
+    }
+    Ok(invoked.result)
+}
+
+#[tauri::command]
+async fn dismiss_notification_actions(
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    state.actions.dismiss(&app, &id);
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_file_watches(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<Vec<FileWatch>, String> {
+    Ok(state.files.list())
+}
+
+#[tauri::command]
+async fn add_file_watch(
+    watch: FileWatch,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<FileWatch, String> {
+    create_file_watch(&state.app_state(&app), watch)
+}
+
+#[tauri::command]
+async fn remove_file_watch(
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    delete_file_watch(&state.app_state(&app), &id, None)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_scheduled_notifications(
+    state: tauri::State<'_, ManagedState>,
+) -> Result<Vec<ScheduledNotification>, String> {
+    Ok(state.scheduled.list())
+}
+
+#[tauri::command]
+async fn remove_scheduled_notification(
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ManagedState>,
+) -> Result<(), String> {
+    cancel_scheduled_notification(&state.app_state(&app), &id, None)?;
+    Ok(())
+}
+
+/// Shows each burst summary once its agent has gone quiet.
+async fn watch_bursts(state: AppState) {
+    let mut ticker = tokio::time::interval(BURST_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        for (agent, merged) in state.coalescer.take_due(unix_millis_now()) {
+            // The notifications that started the burst were already shown.
+            let title = match merged.len() {
+                1 => format!("1 more update from {agent}"),
+                count => format!("{count} more updates from {agent}"),
+            };
+            show_summary(&state, &title, &merged, |notification| {
+                notification.title.clone()
+            })
+            .await;
+        }
+    }
+}
+
+/// Warns about tasks that overran their deadline or stopped sending heartbeats, since the
+/// notification that matters most is often the one a crashed agent never sends.
+async fn watch_tasks(state: AppState) {
+    let mut ticker = tokio::time::interval(TASK_WATCHDOG_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let now = unix_millis_now();
+        for (task, alarm) in state.tasks.take_alarms(&state.app, now) {
+            let notification = task.alarm_notification(alarm, now);
+            if let Err(err) = dispatch_notification(&state, &notification).await {
+                eprintln!("{err}");
+            }
+        }
+    }
+}
+
+/// Notifies when a watched process exits.
+async fn watch_processes(state: AppState) {
+    let mut ticker = tokio::time::interval(PROCESS_POLL_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let watcher = state.processes.clone();
+        let ended = match task::spawn_blocking(move || watcher.take_ended(unix_millis_now())).await
+        {
+            Ok(ended) => ended,
+            Err(err) => {
+                eprintln!("Failed to check watched processes: {err}");
+                continue;
+            }
+        };
+        for ended in ended {
+            if let Err(err) = dispatch_notification(&state, &ended.notification()).await {
+                eprintln!("{err}");
+            }
+        }
+    }
+}
+
+/// Polls file watches and notifies about whatever they noticed.
+async fn watch_files(state: AppState) {
+    let mut ticker = tokio::time::interval(FILE_POLL_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let watcher = state.files.clone();
+        let (events, finished) = match task::spawn_blocking(move || watcher.poll()).await {
+            Ok(polled) => polled,
+            Err(err) => {
+                eprintln!("Failed to check file watches: {err}");
+                continue;
+            }
+        };
+        // One-shot watches that fired are gone; keep them from firing again after a restart.
+        if finished {
+            if let Err(err) = persist_file_watches(&state) {
+                eprintln!("{err}");
+            }
+        }
+        for event in events {
+            if let Err(err) = dispatch_notification(&state, &event.notification()).await {
+                eprintln!("{err}");
+            }
+        }
+    }
+}
+
+/// Polls a probe's target, backing off between attempts, and notifies once it is reachable
+/// or the probe times out.
+async fn run_probe(state: AppState, id: String, target: ProbeTarget) {
+    let client = match probes::client() {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("{err}");
+            state.probes.cancel(&id, None);
+            return;
+        }
+    };
+    let result = loop {
+        let attempt = target.check(&client).await;
+        match state.probes.record(&id, attempt, unix_millis_now()) {
+            Progress::Retry(delay) => tokio::time::sleep(delay).await,
+            Progress::Done(result) => break result,
+            Progress::Cancelled => return,
+        }
+    };
+    if let Err(err) = dispatch_notification(&state, &result.notification()).await {
+        eprintln!("{err}");
+    }
+}
+
+/// Delivers scheduled notifications when they fall due. The first check runs at startup, so
+/// anything that fell due while the app was closed goes out straight away.
+async fn watch_schedule(state: AppState) {
+    let mut ticker = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let now = unix_millis_now();
+        for scheduled in state.scheduled.due(now) {
+            let notification = scheduled.notification(now);
+            let id = scheduled.id;
+            // A failed delivery is recorded in the history like any other, so it is not
+            // retried; only a crash before this point leaves the reminder on disk.
+            if let Err(err) = dispatch_notification_as(&state, &notification, id.clone()).await {
+                eprintln!("{err}");
+            }
+            // Cancelled while it was being dispatched; nothing left to save.
+            if state.scheduled.complete(&id).is_none() {
+                continue;
+            }
+            if let Err(err) = persist_scheduled_notifications(&state) {
+                eprintln!("{err}");
+            }
+        }
+    }
+}
+
+/// Reminds the user about notifications they have not acknowledged, louder each time, and
+/// hands them to the policy's sink once the reminders run out.
+async fn watch_escalations(state: AppState) {
+    let mut ticker = tokio::time::interval(ESCALATION_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let quiet = state.quiet.status().active;
+        let levels = state.levels.read().await.clone();
+        let now = unix_millis_now();
+        // Do Not Disturb holds reminders back the same way it holds notifications back.
+        let steps = state.escalations.take_due(&state.app, now, |level| {
+            quiet && levels.style(level).urgency != Urgency::Critical
+        });
+        for step in steps {
+            match step {
+                Step::Remind(escalation, gain) => {
+                    if let Err(err) = remind(&state, &escalation, gain).await {
+                        eprintln!("{err}");
+                    }
+                }
+                Step::HandOff(escalation, sink) => {
+                    let payload = escalation.sink_payload(now);
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(err) = sink.deliver(&payload).await {
+                            eprintln!("{err}");
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Shows an unacknowledged notification again and plays its sound `gain` times as loud.
+async fn remind(state: &AppState, escalation: &Escalation, gain: f32) -> Result<(), String> {
+    let style = state.levels.read().await.style(escalation.level).clone();
+    show_toast(
+        state,
+        &format!("{}Reminder: {}", style.title_prefix, escalation.title),
+        &format!(
+            "{}: {}\nNot acknowledged yet. Acknowledge it in the Agent Notifications window.",
+            escalation.agent, escalation.content
+        ),
+        style.icon.as_deref(),
+    )?;
+
+    let custom = match escalation.policy.sound.clone() {
+        Some(sound) => match task::spawn_blocking(move || audio::decode_sound(&sound)).await {
+            Ok(Ok(decoded)) => Some(decoded),
+            Ok(Err(err)) => {
+                eprintln!("Falling back to the usual sound: {err}");
+                None
+            }
+            Err(err) => {
+                eprintln!("Failed to load sound: {err}");
+                None
+            }
+        },
+        None => None,
+    };
+    let sounds = state.sounds.read().await;
+    if !sounds.settings().muted {
+        let sound =
+            custom.unwrap_or_else(|| sounds.resolve(&escalation.agent, style.sound.as_deref()));
+        state.player.play(sound.louder(gain));
+    }
+    Ok(())
+}
+
+/// Delivers the Do-Not-Disturb digest as soon as quiet time runs out.
+async fn watch_quiet_hours(state: AppState) {
+    let mut ticker = tokio::time::interval(QUIET_DIGEST_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        deliver_quiet_digest(&state).await;
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    app: tauri::AppHandle,
+    listening: Arc<AtomicBool>,
+    history: Arc<NotificationHistory>,
+    auth: Arc<RwLock<AuthSettings>>,
+    sessions: Arc<SessionRegistry>,
+    prompts: Arc<PromptRegistry>,
+    mcp_tools: Arc<RwLock<McpToolSettings>>,
+    levels: Arc<RwLock<LevelSettings>>,
+    sounds: Arc<RwLock<SoundBank>>,
+    player: Arc<AudioPlayer>,
+    quiet: Arc<QuietHours>,
+    rules: Arc<RwLock<RuleSet>>,
+    coalescer: Arc<Coalescer>,
+    limiter: Arc<RateLimiter>,
+    actions: Arc<ActionRegistry>,
+    tasks: Arc<TaskTracker>,
+    processes: Arc<ProcessWatcher>,
+    files: Arc<FileWatcher>,
+    probes: Arc<ProbeRegistry>,
+    scheduled: Arc<Scheduler>,
+    escalations: Arc<Escalations>,
+}
+
+#[derive(Deserialize)]
+struct NotifyRequest {
+    title: String,
+    content: String,
+    // Optional when the caller authenticates with a token that overrides the agent.
+    #[serde(default)]
+    agent: String,
+    #[serde(default)]
+    level: NotificationLevel,
+    #[serde(default)]
+    silent: bool,
+    /// Opened when the notification is clicked in the settings window.
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    actions: Vec<NotificationAction>,
+    /// Deliver later instead: an RFC 3339 time, or a number of seconds from now.
+    #[serde(default)]
+    deliver_at: Option<String>,
+    #[serde(default)]
+    delay_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HttpSettings {
+    bind_address: String,
+    port: u16,
+    // Loopback is always allowed; these extend the allow-lists for remote callers.
+    #[serde(default)]
+    allowed_origins: Vec<String>,
+    #[serde(default)]
+    allowed_hosts: Vec<String>,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            bind_address: "127.0.0.1".into(),
+            port: 60766,
+            allowed_origins: Vec::new(),
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+struct ManagedState {
+    listening: Arc<AtomicBool>,
+    server_task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    settings: RwLock<HttpSettings>,
+    history: Arc<NotificationHistory>,
+    auth: Arc<RwLock<AuthSettings>>,
+    sessions: Arc<SessionRegistry>,
+    prompts: Arc<PromptRegistry>,
+    mcp_tools: Arc<RwLock<McpToolSettings>>,
+    levels: Arc<RwLock<LevelSettings>>,
+    sounds: Arc<RwLock<SoundBank>>,
+    player: Arc<AudioPlayer>,
+    quiet: Arc<QuietHours>,
+    rules: Arc<RwLock<RuleSet>>,
+    coalescer: Arc<Coalescer>,
+    limiter: Arc<RateLimiter>,
+    actions: Arc<ActionRegistry>,
+    tasks: Arc<TaskTracker>,
+    processes: Arc<ProcessWatcher>,
+    files: Arc<FileWatcher>,
+    probes: Arc<ProbeRegistry>,
+    scheduled: Arc<Scheduler>,
+    escalations: Arc<Escalations>,
+}
+
+impl ManagedState {
+    fn app_state(&self, app: &tauri::AppHandle) -> AppState {
+        AppState {
+            app: app.clone(),
+            listening: self.listening.clone(),
+            history: self.history.clone(),
+            auth: self.auth.clone(),
+            sessions: self.sessions.clone(),
+            prompts: self.prompts.clone(),
+            mcp_tools: self.mcp_tools.clone(),
+            levels: self.levels.clone(),
+            sounds: self.sounds.clone(),
+            player: self.player.clone(),
+            quiet: self.quiet.clone(),
+            rules: self.rules.clone(),
+            coalescer: self.coalescer.clone(),
+            limiter: self.limiter.clone(),
+            actions: self.actions.clone(),
+            tasks: self.tasks.clone(),
+            processes: self.processes.clone(),
+            files: self.files.clone(),
+            probes: self.probes.clone(),
+            scheduled: self.scheduled.clone(),
+            escalations: self.escalations.clone(),
+        }
+    }
+}
+
+// Windows toast text blocks cap at 1024 chars; keep a conservative ceiling to avoid truncation.
+const MAX_NOTIFICATION_BODY_CHARS: usize = 1000;
+// Soft limit to match the SKILL guidance; keeps space for the agent prefix.
+const SOFT_CONTENT_LIMIT_CHARS: usize = 950;
+// Lines listed in a digest or burst summary before it just counts the rest.
+const MAX_SUMMARY_LINES: usize = 8;
+const MAX_PAUSE_MINUTES: u64 = 24 * 60;
+// How often to check for bursts that have gone quiet.
+const BURST_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const TASK_WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const ESCALATION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+// How often to check whether quiet time has ended and a digest is due.
+const QUIET_DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+fn ensure_notification_permission(app: &tauri::AppHandle) {
+    // Best-effort permission check and request so macOS users get the system prompt up front.
+    match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => {}
+        Ok(PermissionState::Prompt | PermissionState::PromptWithRationale) => {
+            if let Err(err) = app.notification().request_permission() {
+                eprintln!("Notification permission request failed: {err}");
+            }
+        }
+        Ok(PermissionState::Denied) => {
+            eprintln!("Notification permission is denied for this app.");
+        }
+        Err(err) => eprintln!("Unable to read notification permission state: {err}"),
+    }
+
+    #[cfg(target_os = "macos")]
+    if tauri::is_dev() {
+        // In dev builds the notification bundle id maps to Terminal; users may need to allow it.
+        eprintln!(
+            "macOS dev note: notifications appear as 'Terminal'. \
+             Enable Terminal notifications in System Settings > Notifications to see them."
+        );
+    }
+}
+
+fn validate_notification_fields(
+    title: &str,
+    content: &str,
+    agent: &str,
+) -> Result<(String, String, String), String> {
+    let title = title.trim();
+    let content = content.trim();
+    let agent = agent.trim();
+
+    if title.is_empty() || content.is_empty() || agent.is_empty() {
+        return Err("'title', 'content', and 'agent' are required".into());
+    }
+
+    let content_len = content.chars().count();
+    if content_len > SOFT_CONTENT_LIMIT_CHARS {
+        return Err(format!(
+            "'content' is too long ({content_len} chars); keep it under {SOFT_CONTENT_LIMIT_CHARS}"
+        ));
+    }
+
+    Ok((title.to_owned(), content.to_owned(), agent.to_owned()))
+}
+
+fn unix_millis_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// A validated notification on its way to the desktop.
+#[derive(Clone)]
+struct Notification {
+    title: String,
+    content: String,
+    agent: String,
+    level: NotificationLevel,
+    /// Skips the sound for just this notification.
+    silent: bool,
+    source: NotificationSource,
+    /// Click-through link.
+    url: Option<String>,
+    actions: Vec<NotificationAction>,
+    /// MCP session to tell about action results.
+    reply_to: Option<String>,
+}
+
+impl Notification {
+    fn is_actionable(&self) -> bool {
+        self.url.is_some() || !self.actions.is_empty()
+    }
+}
+
+/// What happened to a notification that was accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DispatchStatus {
+    Delivered,
+    /// Held back by Do-Not-Disturb for the digest.
+    Queued,
+    /// Dropped by a rule.
+    Muted,
+    /// Identical to a notification shown moments ago; dropped.
+    Duplicate,
+    /// Held back for a summary of a burst from the same agent.
+    Merged,
+}
+
+impl DispatchStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Delivered => "delivered",
+            Self::Queued => "queued",
+            Self::Muted => "muted",
+            Self::Duplicate => "duplicate",
+            Self::Merged => "merged",
+        }
+    }
+
+    /// Whether the notification was folded into another one rather than shown on its own.
+    fn is_merged(self) -> bool {
+        matches!(self, Self::Duplicate | Self::Merged)
+    }
+}
+
+struct Dispatched {
+    /// Stable id for updating, dismissing or polling the notification later.
+    id: String,
+    status: DispatchStatus,
+    /// The rule that applied, if any.
+    rule: Option<MatchedRule>,
+}
+
+async fn dispatch_notification(
+    state: &AppState,
+    notification: &Notification,
+) -> Result<Dispatched, String> {
+    dispatch_notification_as(state, notification, uuid::Uuid::new_v4().to_string()).await
+}
+
+/// Dispatches a notification under an id handed out earlier, such as a scheduled one's.
+async fn dispatch_notification_as(
+    state: &AppState,
+    notification: &Notification,
+    id: String,
+) -> Result<Dispatched, String> {
+    let mut notification = notification.clone();
+    let effect = state.rules.read().await.apply(&mut notification);
+
+    let Notification {
+        title,
+        content,
+        agent,
+        level,
+        silent,
+        source,
+        ..
+    } = &notification;
+    let style = state.levels.read().await.style(*level).clone();
+
+    // Critical-urgency levels are exactly the ones that should get through Do-Not-Disturb,
+    // and they are not held back for a burst summary either.
+    let urgent = style.urgency == Urgency::Critical;
+    let now = unix_millis_now();
+    let status = if effect.muted {
+        DispatchStatus::Muted
+    } else if state.coalescer.is_duplicate(&notification, now) {
+        DispatchStatus::Duplicate
+    } else if !urgent && state.quiet.defer(&notification) {
+        DispatchStatus::Queued
+    } else if !urgent && state.coalescer.merge_into_burst(&notification, now) {
+        DispatchStatus::Merged
+    } else {
+        DispatchStatus::Delivered
+    };
+    // Repeats would otherwise hit a webhook or spawn a command once per retry. Muted
+    // notifications still go out, since a rule may mute one precisely to route it to a sink,
+    // and each notification folded into a burst is forwarded on its own; the summary is only
+    // for the screen.
+    if status != DispatchStatus::Duplicate {
+        forward_to_sinks(&effect.sinks, &notification, effect.rule.as_ref());
+    }
+    // Muted and duplicate notifications are gone for good; anything else can still be acted on.
+    let actionable = notification.is_actionable()
+        && !matches!(status, DispatchStatus::Muted | DispatchStatus::Duplicate);
+    if actionable {
+        state.actions.track(
+            &state.app,
+            ActionableNotification {
+                id: id.clone(),
+                agent: agent.clone(),
+                title: title.clone(),
+                content: content.clone(),
+                url: notification.url.clone(),
+                actions: notification.actions.clone(),
+                created_at: now,
+                results: Vec::new(),
+                dismissed: false,
+                session: notification.reply_to.clone(),
+            },
+        );
+    }
+
+    // Held and merged notifications are the easiest to miss, so they are tracked too; their
+    // reminders wait while Do Not Disturb holds the level.
+    let escalation_policy = match status {
+        DispatchStatus::Delivered | DispatchStatus::Queued | DispatchStatus::Merged => {
+            state.escalations.policy_for(&notification)
+        }
+        DispatchStatus::Muted | DispatchStatus::Duplicate => None,
+    };
+    let result = match status {
+        DispatchStatus::Delivered => {
+            // Desktop notifications cannot carry buttons everywhere, so point at the window.
+            let hint = if escalation_policy.is_some() {
+                "\nAcknowledge it in the Agent Notifications window."
+            } else if actionable {
+                "\nActions are in the Agent Notifications window."
+            } else {
+                ""
+            };
+            show_toast(
+                state,
+                &format!("{}{title}", style.title_prefix),
+                &format!("{agent}: {content}{hint}"),
+                style.icon.as_deref(),
+            )
+        }
+        DispatchStatus::Queued
+        | DispatchStatus::Muted
+        | DispatchStatus::Duplicate
+        | DispatchStatus::Merged => Ok(()),
+    };
+
+    // Record every attempt, including failures, so the history explains missing toasts.
+    state.history.record(NotificationRecord {
+        id: id.clone(),
+        timestamp: unix_millis_now(),
+        title: title.clone(),
+        content: content.clone(),
+        agent: agent.clone(),
+        level: *level,
+        source: *source,
+        outcome: match (&result, status) {
+            (Err(err), _) => DeliveryOutcome::Failed { error: err.clone() },
+            (Ok(()), DispatchStatus::Delivered) => DeliveryOutcome::Delivered,
+            (Ok(()), DispatchStatus::Queued) => DeliveryOutcome::Queued,
+            (Ok(()), DispatchStatus::Muted) => DeliveryOutcome::Muted,
+            (Ok(()), DispatchStatus::Duplicate) => DeliveryOutcome::Duplicate,
+            (Ok(()), DispatchStatus::Merged) => DeliveryOutcome::Merged,
+        },
+        rule: effect.rule.clone(),
+        updated_at: None,
+        dismissed: false,
+        acknowledged_at: None,
+    });
+
+    result?;
+    if let Some(policy) = escalation_policy {
+        state
+            .escalations
+            .track(&state.app, &id, &notification, policy, now);
+    }
+    if status == DispatchStatus::Delivered && !silent && style.plays_sound() {
+        play_notification_sound(state, agent, style.sound.as_deref(), effect.sound).await;
+    }
+    Ok(Dispatched {
+        id,
+        status,
+        rule: effect.rule,
+    })
+}
+
+/// New title, content or level for a notification that was already sent; missing fields are
+/// kept.
+#[derive(Debug, Default, Deserialize)]
+struct NotificationUpdate {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    level: Option<NotificationLevel>,
+    /// Show a fresh desktop notification for this update. Desktop notifications cannot be
+    /// edited once shown, so otherwise only the stored copy changes unless the level goes up.
+    #[serde(default)]
+    alert: bool,
+    #[serde(default)]
+    silent: bool,
+}
+
+/// What happened to an accepted update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UpdateStatus {
+    /// Only the stored copy changed.
+    Updated,
+    /// Shown again as a new desktop notification.
+    Delivered,
+    /// Held back by Do-Not-Disturb for the digest.
+    Queued,
+}
+
+impl UpdateStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Updated => "updated",
+            Self::Delivered => "delivered",
+            Self::Queued => "queued",
+        }
+    }
+}
+
+/// Why a request about an existing notification or task could not be carried out.
+enum ChangeError {
+    /// Unknown id, or sent by an agent the caller's token does not cover.
+    NotFound,
+    UnknownTask,
+    Dismissed,
+    Invalid(String),
+    Limited(RateLimited),
+    Failed(String),
+}
+
+impl ChangeError {
+    fn message(&self) -> String {
+        match self {
+            Self::NotFound => "No notification with that id".into(),
+            Self::UnknownTask => "No running task with that id for this agent".into(),
+            Self::Dismissed => "This notification was dismissed".into(),
+            Self::Invalid(err) | Self::Failed(err) => err.clone(),
+            Self::Limited(limited) => limited.message(),
+        }
+    }
+}
+
+/// Looks up a notification the caller may change; `owner` is the agent a token is limited to.
+fn owned_record(
+    state: &AppState,
+    id: &str,
+    owner: Option<&str>,
+) -> Result<NotificationRecord, ChangeError> {
+    let record = state
+        .history
+        .get(id)
+        .filter(|record| owner.is_none_or(|owner| record.agent == owner))
+        .ok_or(ChangeError::NotFound)?;
+    if record.dismissed {
+        return Err(ChangeError::Dismissed);
+    }
+    Ok(record)
+}
+
+/// Replaces the title, content or level of a sent notification, so progress can be reported
+/// without stacking a new notification for every step.
+async fn update_notification(
+    state: &AppState,
+    id: &str,
+    update: NotificationUpdate,
+    owner: Option<&str>,
+    client: IpAddr,
+) -> Result<(NotificationRecord, UpdateStatus), ChangeError> {
+    let current = owned_record(state, id, owner)?;
+    let (title, content, agent) = validate_notification_fields(
+        update.title.as_deref().unwrap_or(&current.title),
+        update.content.as_deref().unwrap_or(&current.content),
+        &current.agent,
+    )
+    .map_err(ChangeError::Invalid)?;
+    state
+        .limiter
+        .check(&agent, client)
+        .map_err(ChangeError::Limited)?;
+
+    let level = update.level.unwrap_or(current.level);
+    let style = state.levels.read().await.style(level).clone();
+    let notification = Notification {
+        title,
+        content,
+        agent,
+        level,
+        silent: update.silent,
+        source: current.source,
+        url: None,
+        actions: Vec::new(),
+        reply_to: None,
+    };
+    // Muted and duplicate notifications were dropped on purpose; updates do not bring them back.
+    let was_dropped = matches!(
+        current.outcome,
+        DeliveryOutcome::Muted | DeliveryOutcome::Duplicate
+    );
+    let status = if was_dropped || !(update.alert || level > current.level) {
+        UpdateStatus::Updated
+    } else if style.urgency != Urgency::Critical && state.quiet.defer(&notification) {
+        UpdateStatus::Queued
+    } else {
+        UpdateStatus::Delivered
+    };
+    let result = match status {
+        UpdateStatus::Delivered => show_toast(
+            state,
+            &format!("{}{}", style.title_prefix, notification.title),
+            &format!("{}: {}", notification.agent, notification.content),
+            style.icon.as_deref(),
+        ),
+        UpdateStatus::Updated | UpdateStatus::Queued => Ok(()),
+    };
+
+    let now = unix_millis_now();
+    let record = state
+        .history
+        .update(id, |record| {
+            record.title = notification.title.clone();
+            record.content = notification.content.clone();
+            record.level = level;
+            record.updated_at = Some(now);
+            match (&result, status) {
+                (Err(err), _) => record.outcome = DeliveryOutcome::Failed { error: err.clone() },
+                (Ok(()), UpdateStatus::Delivered) => record.outcome = DeliveryOutcome::Delivered,
+                (Ok(()), UpdateStatus::Queued) => record.outcome = DeliveryOutcome::Queued,
+                (Ok(()), UpdateStatus::Updated) => {}
+            }
+        })
+        .ok_or(ChangeError::NotFound)?;
+    state
+        .actions
+        .update(&state.app, id, &notification.title, &notification.content);
+    state
+        .escalations
+        .update(&state.app, id, &notification.title, &notification.content);
+
+    result.map_err(ChangeError::Failed)?;
+    // Raising the level can bring a notification under an escalation policy.
+    if matches!(status, UpdateStatus::Delivered | UpdateStatus::Queued)
+        && record.acknowledged_at.is_none()
+        && state.escalations.get(id).is_none()
+    {
+        if let Some(policy) = state.escalations.policy_for(&notification) {
+            state
+                .escalations
+                .track(&state.app, id, &notification, policy, now);
+        }
+    }
+    if status == UpdateStatus::Delivered && !notification.silent && style.plays_sound() {
+        play_notification_sound(state, &notification.agent, style.sound.as_deref(), None).await;
+    }
+    Ok((record, status))
+}
+
+/// A task an agent is starting, with optional watchdog limits.
+#[derive(Debug, Deserialize)]
+struct TaskStart {
+    id: String,
+    title: String,
+    #[serde(default)]
+    agent: String,
+    /// Warn once the task has run this long.
+    #[serde(default)]
+    deadline_seconds: Option<u64>,
+    /// Warn when heartbeats stop for this long.
+    #[serde(default)]
+    heartbeat_seconds: Option<u64>,
+}
+
+/// Starts timing a task; returns it and whether an earlier run with the same id was replaced.
+fn start_task(
+    state: &AppState,
+    start: TaskStart,
+    source: NotificationSource,
+) -> Result<(RunningTask, bool), String> {
+    let id = tasks::validate_task_id(&start.id)?;
+    let (title, agent) = (start.title.trim(), start.agent.trim());
+    if title.is_empty() || agent.is_empty() {
+        return Err("'id', 'title', and 'agent' are required".into());
+    }
+    tasks::validate_watchdog(start.deadline_seconds, start.heartbeat_seconds)?;
+    let now = unix_millis_now();
+    let task = RunningTask {
+        id,
+        agent: agent.to_owned(),
+        title: title.to_owned(),
+        started_at: now,
+        deadline_at: start.deadline_seconds.map(|secs| now + secs * 1000),
+        heartbeat_interval_seconds: start.heartbeat_seconds,
+        last_heartbeat_at: now,
+        overdue: false,
+        stalled: false,
+        source,
+    };
+    let restarted = state.tasks.start(&state.app, task.clone());
+    Ok((task, restarted))
+}
+
+/// Records a heartbeat for a running task.
+fn task_heartbeat(state: &AppState, id: &str, agent: &str) -> Result<RunningTask, ChangeError> {
+    let id = tasks::validate_task_id(id).map_err(ChangeError::Invalid)?;
+    state
+        .tasks
+        .heartbeat(&state.app, agent.trim(), &id, unix_millis_now())
+        .ok_or(ChangeError::UnknownTask)
+}
+
+/// Starts watching a process for its exit; the agent must already be resolved.
+async fn watch_process(
+    state: &AppState,
+    request: ProcessWatchRequest,
+    source: NotificationSource,
+) -> Result<ProcessWatch, String> {
+    let watcher = state.processes.clone();
+    task::spawn_blocking(move || watcher.watch(request, source, unix_millis_now()))
+        .await
+        .map_err(|err| format!("Failed to look up the process: {err}"))?
+}
+
+/// Starts waiting for a TCP port or URL to become reachable; the agent must already be
+/// resolved.
+fn wait_for(
+    state: &AppState,
+    request: ProbeRequest,
+    source: NotificationSource,
+) -> Result<Probe, String> {
+    let (probe, target) = Probe::new(request, source, unix_millis_now())?;
+    state.probes.add(probe.clone())?;
+    tauri::async_runtime::spawn(run_probe(state.clone(), probe.id.clone(), target));
+    Ok(probe)
+}
+
+fn persist_scheduled_notifications(state: &AppState) -> Result<(), String> {
+    persist_stored_setting(
+        &state.app,
+        schedule::SCHEDULED_NOTIFICATIONS_KEY,
+        &state.scheduled.list(),
+        "scheduled notifications",
+    )
+}
+
+/// Saves a validated notification for delivery at `deliver_at`.
+fn schedule_notification(
+    state: &AppState,
+    notification: Notification,
+    deliver_at: u64,
+) -> Result<ScheduledNotification, String> {
+    let scheduled = ScheduledNotification::new(notification, deliver_at, unix_millis_now());
+    state.scheduled.add(scheduled.clone())?;
+    if let Err(err) = persist_scheduled_notifications(state) {
+        state.scheduled.cancel(&scheduled.id, None);
+        return Err(err);
+    }
+    Ok(scheduled)
+}
+
+/// Cancels a scheduled notification; `owner` limits it to ones from that agent.
+fn cancel_scheduled_notification(
+    state: &AppState,
+    id: &str,
+    owner: Option<&str>,
+) -> Result<Option<ScheduledNotification>, String> {
+    let Some(scheduled) = state.scheduled.cancel(id, owner) else {
+        return Ok(None);
+    };
+    persist_scheduled_notifications(state)?;
+    Ok(Some(scheduled))
+}
+
+fn persist_file_watches(state: &AppState) -> Result<(), String> {
+    persist_stored_setting(
+        &state.app,
+        filewatch::FILE_WATCHES_KEY,
+        &state.files.list(),
+        "file watches",
+    )
+}
+
+/// Checks and saves a new file watch; the agent must already be resolved.
+fn create_file_watch(state: &AppState, mut watch: FileWatch) -> Result<FileWatch, String> {
+    watch.prepare(unix_millis_now())?;
+    state.files.add(watch.clone())?;
+    if let Err(err) = persist_file_watches(state) {
+        state.files.remove(&watch.id, None);
+        return Err(err);
+    }
+    Ok(watch)
+}
+
+/// Removes a file watch; `owner` limits it to watches for that agent.
+fn delete_file_watch(
+    state: &AppState,
+    id: &str,
+    owner: Option<&str>,
+) -> Result<Option<FileWatch>, String> {
+    let Some(watch) = state.files.remove(id, owner) else {
+        return Ok(None);
+    };
+    persist_file_watches(state)?;
+    Ok(Some(watch))
+}
+
+/// How a task ended, as reported by the agent.
+#[derive(Debug, Default, Deserialize)]
+struct TaskFinish {
+    #[serde(default)]
+    outcome: TaskOutcome,
+    /// Appended to the elapsed time in the notification.
+    #[serde(default)]
+    summary: Option<String>,
+    /// Defaults to success, error or warning depending on the outcome.
+    #[serde(default)]
+    level: Option<NotificationLevel>,
+    #[serde(default)]
+    silent: bool,
+}
+
+/// Stops timing a task and announces how it went and how long it took.
+async fn finish_task(
+    state: &AppState,
+    id: &str,
+    agent: &str,
+    finish: TaskFinish,
+    source: NotificationSource,
+    client: IpAddr,
+) -> Result<(FinishedTask, Dispatched), ChangeError> {
+    let id = tasks::validate_task_id(id).map_err(ChangeError::Invalid)?;
+    let agent = agent.trim();
+    if agent.is_empty() {
+        return Err(ChangeError::Invalid("'agent' is required".into()));
+    }
+    let summary_len = finish.summary.as_deref().map_or(0, |s| s.chars().count());
+    if summary_len > SOFT_CONTENT_LIMIT_CHARS {
+        return Err(ChangeError::Invalid(format!(
+            "'summary' is too long ({summary_len} chars); keep it under {SOFT_CONTENT_LIMIT_CHARS}"
+        )));
+    }
+    // Checked first so a rate-limited finish leaves the task running for the retry.
+    state
+        .limiter
+        .check(agent, client)
+        .map_err(ChangeError::Limited)?;
+
+    let finished = state
+        .tasks
+        .finish(&state.app, agent, &id, finish.outcome, unix_millis_now())
+        .ok_or(ChangeError::UnknownTask)?;
+    let notification = finished.notification(
+        finish.summary.as_deref(),
+        finish.level,
+        finish.silent,
+        source,
+    );
+    let dispatched = dispatch_notification(state, &notification)
+        .await
+        .map_err(ChangeError::Failed)?;
+    Ok((finished, dispatched))
+}
+
+/// Marks a notification as done with: it leaves the window and can no longer be updated.
+/// Desktop notifications already shown stay until the system clears them.
+fn dismiss_notification(
+    state: &AppState,
+    id: &str,
+    owner: Option<&str>,
+) -> Result<(), ChangeError> {
+    owned_record(state, id, owner)?;
+    state
+        .history
+        .update(id, |record| record.dismissed = true)
+        .ok_or(ChangeError::NotFound)?;
+    state.actions.dismiss(&state.app, id);
+    state.escalations.stop(&state.app, id);
+    Ok(())
+}
+
+/// Records that the user has seen a notification, which stops any escalation. Acknowledging
+/// twice keeps the first time.
+fn acknowledge(state: &AppState, id: &str, owner: Option<&str>) -> Result<(), ChangeError> {
+    let agent = state
+        .history
+        .get(id)
+        .map(|record| record.agent)
+        .or_else(|| state.escalations.get(id).map(|escalation| escalation.agent))
+        .ok_or(ChangeError::NotFound)?;
+    if owner.is_some_and(|owner| owner != agent) {
+        return Err(ChangeError::NotFound);
+    }
+    let now = unix_millis_now();
+    state.history.update(id, |record| {
+        record.acknowledged_at.get_or_insert(now);
+    });
+    state.escalations.stop(&state.app, id);
+    Ok(())
+}
+
+/// Sends a copy of the notification to each sink in the background; failures are only logged.
+fn forward_to_sinks(
+    sinks: &[sinks::Sink],
+    notification: &Notification,
+    rule: Option<&MatchedRule>,
+) {
+    if sinks.is_empty() {
+        return;
+    }
+    let payload = Arc::new(json!({
+        "title": notification.title,
+        "content": notification.content,
+        "agent": notification.agent,
+        "level": notification.level,
+        "source": notification.source,
+        "rule": rule,
+        "timestamp": unix_millis_now(),
+    }));
+    for sink in sinks {
+        let sink = sink.clone();
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(err) = sink.deliver(&payload).await {
+                eprintln!("{err}");
+            }
+        });
+    }
+}
+
+fn show_toast(state: &AppState, title: &str, body: &str, icon: Option<&str>) -> Result<(), String> {
+    let limited_body: String = body.chars().take(MAX_NOTIFICATION_BODY_CHARS).collect();
+    let mut builder = state
+        .app
+        .notification()
+        .builder()
+        .title(title)
+        .body(&limited_body);
+    if let Some(icon) = icon {
+        builder = builder.icon(icon);
+    }
+    builder
+        .show()
+        .map_err(|err| format!("Failed to dispatch notification: {err}"))
+}
+
+/// Plays the rule's sound if one applied, otherwise the agent's, level's or default sound.
+async fn play_notification_sound(
+    state: &AppState,
+    agent: &str,
+    level_sound: Option<&str>,
+    rule_sound: Option<DecodedSound>,
+) {
+    let sounds = state.sounds.read().await;
+    if !sounds.settings().muted {
+        state
+            .player
+            .play(rule_sound.unwrap_or_else(|| sounds.resolve(agent, level_sound)));
+    }
+}
+
+/// Shows everything Do-Not-Disturb held back as one notification, once quiet time is over.
+async fn deliver_quiet_digest(state: &AppState) {
+    let queued = state.quiet.take_digest();
+    let title = match queued.len() {
+        0 => return,
+        1 => "1 notification during Do Not Disturb".to_owned(),
+        count => format!("{count} notifications during Do Not Disturb"),
+    };
+    show_summary(state, &title, &queued, |notification| {
+        format!("{}: {}", notification.agent, notification.title)
+    })
+    .await;
+}
+
+/// Shows several held-back notifications as one, styled by the most serious level among them.
+async fn show_summary(
+    state: &AppState,
+    title: &str,
+    notifications: &[Notification],
+    line: impl Fn(&Notification) -> String,
+) {
+    let Some(level) = notifications
+        .iter()
+        .map(|notification| notification.level)
+        .max()
+    else {
+        return;
+    };
+    let style = state.levels.read().await.style(level).clone();
+
+    let mut lines: Vec<String> = notifications
+        .iter()
+        .take(MAX_SUMMARY_LINES)
+        .map(line)
+        .collect();
+    if notifications.len() > MAX_SUMMARY_LINES {
+        lines.push(format!(
+            "…and {} more",
+            notifications.len() - MAX_SUMMARY_LINES
+        ));
+    }
+
+    if let Err(err) = show_toast(
+        state,
+        &format!("{}{title}", style.title_prefix),
+        &lines.join("\n"),
+        style.icon.as_deref(),
+    ) {
+        eprintln!("{err}");
+        return;
+    }
+    if style.plays_sound()
+        && notifications
+            .iter()
+            .any(|notification| !notification.silent)
+    {
+        let agent = notifications[0].agent.as_str();
+        play_notification_sound(state, agent, style.sound.as_deref(), None).await;
+    }
+}
+
+async fn notify_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Json(payload): Json<NotifyRequest>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    let agent = match resolve_request_agent(identity, payload.agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "message": err }))).into_response()
+        }
+    };
+
+    let title = payload.title.trim();
+    let content = payload.content.trim();
+    let agent = agent.trim();
+
+    if title.is_empty() || content.is_empty() || agent.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": "'title', 'content', and 'agent' are required" })),
+        )
+            .into_response();
+    }
+
+    let mut actions = payload.actions;
+    let checked = actions::validate_actions(&mut actions)
+        .and_then(|()| payload.url.as_deref().map_or(Ok(()), actions::validate_url))
+        .and_then(|()| {
+            schedule::delivery_time(
+                payload.deliver_at.as_deref(),
+                payload.delay_seconds,
+                unix_millis_now(),
+            )
+        });
+    let deliver_at = match checked {
+        Ok(deliver_at) => deliver_at,
+        Err(err) => {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "message": err }))).into_response()
+        }
+    };
+
+    if let Err(limited) = state.limiter.check(agent, client.ip()) {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, limited.retry_after_secs().to_string())],
+            Json(json!({
+                "message": limited.message(),
+                "scope": limited.scope,
+                "retry_after_seconds": limited.retry_after_secs()
+            })),
+        )
+            .into_response();
+    }
+
+    let notification = Notification {
+        title: title.to_owned(),
+        content: content.to_owned(),
+        agent: agent.to_owned(),
+        level: payload.level,
+        silent: payload.silent,
+        source: NotificationSource::Http,
+        url: payload.url,
+        actions,
+        reply_to: None,
+    };
+    if let Some(deliver_at) = deliver_at {
+        return match schedule_notification(&state, notification, deliver_at) {
+            Ok(scheduled) => (
+                StatusCode::ACCEPTED,
+                Json(json!({
+                    "id": scheduled.id,
+                    "message": "Notification scheduled",
+                    "status": "scheduled",
+                    "deliver_at": scheduled.deliver_at
+                })),
+            )
+                .into_response(),
+            Err(err) => (StatusCode::BAD_REQUEST, Json(json!({ "message": err }))).into_response(),
+        };
+    }
+    match dispatch_notification(&state, &notification).await {
+        Ok(Dispatched { id, status, rule }) => {
+            let (code, message, label) = match status {
+                DispatchStatus::Delivered => {
+                    (StatusCode::OK, "Notification dispatched", "delivered")
+                }
+                DispatchStatus::Queued => (
+                    StatusCode::ACCEPTED,
+                    "Notification queued until Do Not Disturb ends",
+                    "queued",
+                ),
+                DispatchStatus::Muted => (StatusCode::OK, "Notification muted by a rule", "muted"),
+                DispatchStatus::Duplicate => (
+                    StatusCode::OK,
+                    "Duplicate of a recent notification; not shown again",
+                    "duplicate",
+                ),
+                DispatchStatus::Merged => (
+                    StatusCode::ACCEPTED,
+                    "Notification merged into a summary of recent updates",
+                    "merged",
+                ),
+            };
+            (
+                code,
+                Json(json!({
+                    "id": id,
+                    "message": message,
+                    "status": label,
+                    "merged": status.is_merged(),
+                    "rule": rule
+                })),
+            )
+                .into_response()
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "message": "Failed to dispatch notification" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn change_error_response(err: ChangeError) -> Response {
+    let code = match &err {
+        ChangeError::NotFound | ChangeError::UnknownTask => StatusCode::NOT_FOUND,
+        ChangeError::Dismissed => StatusCode::CONFLICT,
+        ChangeError::Invalid(_) => StatusCode::BAD_REQUEST,
+        ChangeError::Failed(err) => {
+            eprintln!("{err}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        ChangeError::Limited(limited) => {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, limited.retry_after_secs().to_string())],
+                Json(json!({
+                    "message": limited.message(),
+                    "scope": limited.scope,
+                    "retry_after_seconds": limited.retry_after_secs()
+                })),
+            )
+                .into_response()
+        }
+    };
+    (code, Json(json!({ "message": err.message() }))).into_response()
+}
+
+async fn update_notification_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Path(id): Path<String>,
+    Json(update): Json<NotificationUpdate>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    let owner = identity.map(|Extension(identity)| identity.label);
+    match update_notification(&state, &id, update, owner.as_deref(), client.ip()).await {
+        Ok((record, status)) => (
+            StatusCode::OK,
+            Json(json!({
+                "id": record.id,
+                "status": status.as_str(),
+                "title": record.title,
+                "content": record.content,
+                "level": record.level
+            })),
+        )
+            .into_response(),
+        Err(err) => change_error_response(err),
+    }
+}
+
+async fn dismiss_notification_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    let owner = identity.map(|Extension(identity)| identity.label);
+    match dismiss_notification(&state, &id, owner.as_deref()) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => change_error_response(err),
+    }
+}
+
+async fn acknowledge_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    let owner = identity.map(|Extension(identity)| identity.label);
+    match acknowledge(&state, &id, owner.as_deref()) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => change_error_response(err),
+    }
+}
+
+async fn escalations_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        );
+    }
+
+    let owner = identity.map(|Extension(identity)| identity.label);
+    let escalations = visible_to(state.escalations.list(), owner.as_deref(), |escalation| {
+        &escalation.agent
+    });
+    (StatusCode::OK, Json(json!({ "escalations": escalations })))
+}
+
+/// What a caller may list: everything without a token, otherwise only what was sent under the
+/// token's label, the same rule the cancel endpoints apply.
+fn visible_to<T>(items: Vec<T>, owner: Option<&str>, agent: impl Fn(&T) -> &String) -> Vec<T> {
+    match owner {
+        Some(owner) => items
+            .into_iter()
+            .filter(|item| agent(item) == owner)
+            .collect(),
+        None => items,
+    }
+}
+
+/// Applies the bearer token's agent policy to the `agent` the caller sent.
+fn resolve_request_agent(
+    identity: Option<Extension<TokenIdentity>>,
+    agent: String,
+) -> Result<String, String> {
+    match identity {
+        Some(Extension(identity)) => identity.resolve_agent(&agent),
+        None => Ok(agent),
+    }
+}
+
+async fn start_task_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Json(mut payload): Json<TaskStart>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    payload.agent = match resolve_request_agent(identity, payload.agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "message": err }))).into_response()
+        }
+    };
+    match start_task(&state, payload, NotificationSource::Http) {
+        Ok((task, restarted)) => {
+            let mut body = json!(task);
+            body["restarted"] = json!(restarted);
+            (StatusCode::CREATED, Json(body)).into_response()
+        }
+        Err(err) => (StatusCode::BAD_REQUEST, Json(json!({ "message": err }))).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct FinishTaskRequest {
+    #[serde(default)]
+    agent: String,
+    #[serde(flatten)]
+    finish: TaskFinish,
+}
+
+async fn finish_task_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Path(id): Path<String>,
+    Json(payload): Json<FinishTaskRequest>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    let agent = match resolve_request_agent(identity, payload.agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "message": err }))).into_response()
+        }
+    };
+    match finish_task(
+        &state,
+        &id,
+        &agent,
+        payload.finish,
+        NotificationSource::Http,
+        client.ip(),
+    )
+    .await
+    {
+        Ok((finished, dispatched)) => {
+            let mut body = json!(finished);
+            body["notification"] = json!({
+                "id": dispatched.id,
+                "status": dispatched.status.as_str(),
+                "rule": dispatched.rule
+            });
+            (StatusCode::OK, Json(body)).into_response()
+        }
+        Err(err) => change_error_response(err),
+    }
+}
+
+#[derive(Deserialize)]
+struct TaskHeartbeatRequest {
+    #[serde(default)]
+    agent: String,
+}
+
+async fn task_heartbeat_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+    Json(payload): Json<TaskHeartbeatRequest>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    let agent = match resolve_request_agent(identity, payload.agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "message": err }))).into_response()
+        }
+    };
+    match task_heartbeat(&state, &id, &agent) {
+        Ok(task) => (StatusCode::OK, Json(json!(task))).into_response(),
+        Err(err) => change_error_response(err),
+    }
+}
+
+async fn watch_process_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Json(mut payload): Json<ProcessWatchRequest>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    payload.agent = match resolve_request_agent(identity, payload.agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "message": err }))).into_response()
         }
-        Err(err) => eprintln!("Unable to read notification permission state: {err}"),
+    };
+    match watch_process(&state, payload, NotificationSource::Http).await {
+        Ok(watch) => (StatusCode::CREATED, Json(json!(watch))).into_response(),
+        Err(err) => (StatusCode::BAD_REQUEST, Json(json!({ "message": err }))).into_response(),
     }
+}
 
-    #[cfg(target_os = "macos")]
-    if tauri::is_dev() {
-        // In dev builds the notification bundle id maps to Terminal; users may need to allow it.
-        eprintln!(
-            "macOS dev note: notifications appear as 'Terminal'. \
-             Enable Terminal notifications in System Settings > Notifications to see them."
+async fn process_watches_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
         );
     }
-}
 
-fn validate_notification_fields(
-    title: &str,
-    content: &str,
-    agent: &str,
-) -> Result<(String, String, String), String> {
-    let title = title.trim();
-    let content = content.trim();
-    let agent = agent.trim();
+    let owner = identity.map(|Extension(identity)| identity.label);
+    let watches = visible_to(state.processes.list(), owner.as_deref(), |watch| {
+        &watch.agent
+    });
+    (StatusCode::OK, Json(json!({ "watches": watches })))
+}
 
-    if title.is_empty() || content.is_empty() || agent.is_empty() {
-        return Err("'title', 'content', and 'agent' are required".into());
+async fn cancel_process_watch_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
     }
 
-    let content_len = content.chars().count();
-    if content_len > SOFT_CONTENT_LIMIT_CHARS {
-        return Err(format!(
-            "'content' is too long ({content_len} chars); keep it under {SOFT_CONTENT_LIMIT_CHARS}"
-        ));
+    let owner = identity.map(|Extension(identity)| identity.label);
+    match state.processes.cancel(&id, owner.as_deref()) {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "No process watch with that id" })),
+        )
+            .into_response(),
     }
-
-    Ok((title.to_owned(), content.to_owned(), agent.to_owned()))
 }
 
-fn dispatch_notification(
-    state: &AppState,
-    title: &str,
-    content: &str,
-    agent: &str,
-) -> Result<(), String> {
-    let body = format!("{agent}: {content}");
-    let limited_content: String = body.chars().take(MAX_NOTIFICATION_BODY_CHARS).collect();
-
-    state
-        .app
-        .notification()
-        .builder()
-        .title(title)
-        .body(&limited_content)
-        .show()
-        .map_err(|err| format!("Failed to dispatch notification: {err}"))?;
+async fn scheduled_notifications_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        );
+    }
 
-    play_notification_sound();
-    Ok(())
+    let owner = identity.map(|Extension(identity)| identity.label);
+    let scheduled = visible_to(state.scheduled.list(), owner.as_deref(), |scheduled| {
+        &scheduled.agent
+    });
+    (StatusCode::OK, Json(json!({ "scheduled": scheduled })))
 }
 
-fn notify_tool_descriptor() -> Value {
-    json!({
-        "name": "notify",
-        "description": "Send a desktop notification via the Agent Notifications app with title, content, and agent label.",
-        "inputSchema": {
-            "type": "object",
-            "properties": {
-                "title": { "type": "string", "minLength": 1 },
-                "content": { "type": "string", "minLength": 1, "maxLength": SOFT_CONTENT_LIMIT_CHARS as i64 },
-                "agent": { "type": "string", "minLength": 1 }
-            },
-            "required": ["title", "content", "agent"],
-            "additionalProperties": false
+async fn cancel_scheduled_notification_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
+
+    let owner = identity.map(|Extension(identity)| identity.label);
+    match cancel_scheduled_notification(&state, &id, owner.as_deref()) {
+        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "No scheduled notification with that id" })),
+        )
+            .into_response(),
+        Err(err) => {
+            eprintln!("{err}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "message": "Failed to save scheduled notifications" })),
+            )
+                .into_response()
         }
-    })
+    }
 }
 
-fn jsonrpc_success(id: Value, result: Value) -> Value {
-    json!({
-        "jsonrpc": "2.0",
-        "id": id,
-        "result": result
-    })
-}
+async fn wait_for_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Json(mut payload): Json<ProbeRequest>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
+    }
 
-fn jsonrpc_error(id: Option<Value>, code: i64, message: &str) -> Value {
-    json!({
-        "jsonrpc": "2.0",
-        "id": id.unwrap_or(Value::Null),
-        "error": { "code": code, "message": message }
-    })
+    payload.agent = match resolve_request_agent(identity, payload.agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "message": err }))).into_response()
+        }
+    };
+    match wait_for(&state, payload, NotificationSource::Http) {
+        Ok(probe) => (StatusCode::CREATED, Json(json!(probe))).into_response(),
+        Err(err) => (StatusCode::BAD_REQUEST, Json(json!({ "message": err }))).into_response(),
+    }
 }
 
-async fn notify_handler(
+async fn probes_handler(
     State(state): State<AppState>,
-    Json(payload): Json<NotifyRequest>,
+    identity: Option<Extension<TokenIdentity>>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     if !state.listening.load(Ordering::SeqCst) {
         return (
@@ -267,36 +2308,56 @@ async fn notify_handler(
         );
     }
 
-    let title = payload.title.trim();
-    let content = payload.content.trim();
-    let agent = payload.agent.trim();
+    let owner = identity.map(|Extension(identity)| identity.label);
+    let probes = visible_to(state.probes.list(), owner.as_deref(), |probe| &probe.agent);
+    (StatusCode::OK, Json(json!({ "probes": probes })))
+}
 
-    if title.is_empty() || content.is_empty() || agent.is_empty() {
+async fn cancel_probe_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
         return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({ "message": "'title', 'content', and 'agent' are required" })),
-        );
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        )
+            .into_response();
     }
 
-    if let Err(err) = dispatch_notification(&state, title, content, agent) {
-        eprintln!("{err}");
+    let owner = identity.map(|Extension(identity)| identity.label);
+    match state.probes.cancel(&id, owner.as_deref()) {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "No probe with that id" })),
+        )
+            .into_response(),
+    }
+}
+
+async fn file_watches_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !state.listening.load(Ordering::SeqCst) {
         return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "message": "Failed to dispatch notification" })),
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
         );
     }
 
-    (
-        StatusCode::OK,
-        Json(json!({ "message": "Notification dispatched" })),
-    )
+    let owner = identity.map(|Extension(identity)| identity.label);
+    let watches = visible_to(state.files.list(), owner.as_deref(), |watch| &watch.agent);
+    (StatusCode::OK, Json(json!({ "watches": watches })))
 }
 
-async fn mcp_post_handler(
+async fn create_file_watch_handler(
     State(state): State<AppState>,
-    _headers: HeaderMap,
-    Json(body): Json<Value>,
-) -> impl IntoResponse {
+    identity: Option<Extension<TokenIdentity>>,
+    Json(mut payload): Json<FileWatch>,
+) -> Response {
     if !state.listening.load(Ordering::SeqCst) {
         return (
             StatusCode::SERVICE_UNAVAILABLE,
@@ -305,172 +2366,128 @@ async fn mcp_post_handler(
             .into_response();
     }
 
-    // If this is a response or notification (no method), acknowledge and stop.
-    if body.get("method").is_none() {
-        return StatusCode::ACCEPTED.into_response();
-    }
-
-    // Notifications with a method but no id: accept and do nothing.
-    if body.get("id").is_none() {
-        return StatusCode::ACCEPTED.into_response();
+    payload.agent = match resolve_request_agent(identity, payload.agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "message": err }))).into_response()
+        }
+    };
+    payload.source = NotificationSource::Http;
+    match create_file_watch(&state, payload) {
+        Ok(watch) => (StatusCode::CREATED, Json(json!(watch))).into_response(),
+        Err(err) => (StatusCode::BAD_REQUEST, Json(json!({ "message": err }))).into_response(),
     }
+}
 
-    let Some(method) = body.get("method").and_then(Value::as_str) else {
+async fn delete_file_watch_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
         return (
-            StatusCode::OK,
-            Json(jsonrpc_error(
-                None,
-                -32600,
-                "Invalid request: method must be a string",
-            )),
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
         )
             .into_response();
-    };
+    }
 
-    let id = body.get("id").cloned().unwrap_or(Value::Null);
-    let params = body.get("params");
-
-    match method {
-        "initialize" => {
-            let result = json!({
-                "protocolVersion": MCP_PROTOCOL_VERSION,
-                "serverInfo": {
-                    "name": "agent-notifications",
-                    "version": env!("CARGO_PKG_VERSION"),
-                },
-                "capabilities": {
-                    "tools": { "listChanged": false }
-                }
-            });
-            (StatusCode::OK, Json(jsonrpc_success(id, result))).into_response()
-        }
-        "tools/list" => {
-            let result = json!({
-                "tools": [notify_tool_descriptor()],
-                "nextCursor": Value::Null
-            });
-            (StatusCode::OK, Json(jsonrpc_success(id, result))).into_response()
+    let owner = identity.map(|Extension(identity)| identity.label);
+    match delete_file_watch(&state, &id, owner.as_deref()) {
+        Ok(Some(_)) => StatusCode::NO_CONTENT.into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "No file watch with that id" })),
+        )
+            .into_response(),
+        Err(err) => {
+            eprintln!("{err}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "message": err })),
+            )
+                .into_response()
         }
-        "tools/call" => {
-            let Some(param_obj) = params.and_then(Value::as_object) else {
-                return (
-                    StatusCode::OK,
-                    Json(jsonrpc_error(
-                        Some(id),
-                        -32602,
-                        "Invalid params: expected object",
-                    )),
-                )
-                    .into_response();
-            };
-
-            let Some(tool_name) = param_obj.get("name").and_then(Value::as_str) else {
-                return (
-                    StatusCode::OK,
-                    Json(jsonrpc_error(
-                        Some(id),
-                        -32602,
-                        "Invalid params: missing tool name",
-                    )),
-                )
-                    .into_response();
-            };
-
-            if tool_name != "notify" {
-                return (
-                    StatusCode::OK,
-                    Json(jsonrpc_error(Some(id), -32601, "Tool not found")),
-                )
-                    .into_response();
-            }
-
-            let Some(arguments) = param_obj.get("arguments").and_then(Value::as_object) else {
-                return (
-                    StatusCode::OK,
-                    Json(jsonrpc_error(
-                        Some(id),
-                        -32602,
-                        "Invalid params: 'arguments' must be an object",
-                    )),
-                )
-                    .into_response();
-            };
-
-            let title = arguments
-                .get("title")
-                .and_then(Value::as_str)
-                .unwrap_or_default();
-            let content = arguments
-                .get("content")
-                .and_then(Value::as_str)
-                .unwrap_or_default();
-            let agent = arguments
-                .get("agent")
-                .and_then(Value::as_str)
-                .unwrap_or_default();
+    }
+}
 
-            let Ok((title, content, agent)) = validate_notification_fields(title, content, agent)
-            else {
-                return (
-                    StatusCode::OK,
-                    Json(jsonrpc_error(
-                        Some(id),
-                        -32602,
-                        "Invalid params: 'title', 'content', and 'agent' are required and must be within limits",
-                    )),
-                )
-                    .into_response();
-            };
+async fn tasks_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        );
+    }
 
-            if let Err(err) = dispatch_notification(&state, &title, &content, &agent) {
-                eprintln!("{err}");
-                return (
-                    StatusCode::OK,
-                    Json(jsonrpc_error(
-                        Some(id),
-                        -32000,
-                        "Failed to dispatch notification",
-                    )),
-                )
-                    .into_response();
-            }
+    let owner = identity.map(|Extension(identity)| identity.label);
+    let now = unix_millis_now();
+    let tasks: Vec<serde_json::Value> =
+        visible_to(state.tasks.list(), owner.as_deref(), |task| &task.agent)
+            .into_iter()
+            .map(|task| {
+                let elapsed = now.saturating_sub(task.started_at);
+                let mut value = json!(task);
+                value["elapsed_ms"] = json!(elapsed);
+                value
+            })
+            .collect();
+    (StatusCode::OK, Json(json!({ "tasks": tasks })))
+}
 
-            let result = json!({
-                "content": [
-                    {
-                        "type": "text",
-                        "text": format!("Notification sent: {title}")
-                    }
-                ],
-                "isError": false
-            });
+async fn notification_actions_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !state.listening.load(Ordering::SeqCst) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "message": "Server is not listening" })),
+        );
+    }
 
-            (StatusCode::OK, Json(jsonrpc_success(id, result))).into_response()
-        }
-        _ => (
+    // Results can hold reply text typed by the user, so a token only sees its own.
+    let owner = identity.map(|Extension(identity)| identity.label);
+    let entry = state
+        .actions
+        .get(&id)
+        .filter(|entry| owner.is_none_or(|owner| entry.agent == owner));
+    match entry {
+        Some(entry) => (
             StatusCode::OK,
-            Json(jsonrpc_error(Some(id), -32601, "Method not found")),
-        )
-            .into_response(),
+            Json(json!({
+                "id": entry.id,
+                "results": entry.results,
+                "dismissed": entry.dismissed
+            })),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "No actionable notification with that id" })),
+        ),
     }
 }
 
-async fn mcp_get_handler(State(state): State<AppState>) -> impl IntoResponse {
+async fn notifications_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    Query(mut query): Query<HistoryQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
     if !state.listening.load(Ordering::SeqCst) {
         return (
             StatusCode::SERVICE_UNAVAILABLE,
             Json(json!({ "message": "Server is not listening" })),
-        )
-            .into_response();
+        );
     }
 
-    let stream = IntervalStream::new(time::interval(Duration::from_secs(25)))
-        .map(|_| Ok::<Event, Infallible>(Event::default().comment("keep-alive")));
-
-    Sse::new(stream)
-        .keep_alive(KeepAlive::new().interval(Duration::from_secs(25)))
-        .into_response()
+    // Like the endpoints that change notifications, a token only sees its own.
+    query.owner = identity.map(|Extension(identity)| identity.label);
+    (StatusCode::OK, Json(json!(state.history.query(&query))))
 }
+
 fn validate_http_settings(settings: &HttpSettings) -> Result<(), String> {
     if settings.bind_address.trim().is_empty() {
         return Err("Bind address cannot be empty".into());
@@ -478,54 +2495,113 @@ fn validate_http_settings(settings: &HttpSettings) -> Result<(), String> {
     if settings.port == 0 {
         return Err("Port must be between 1 and 65535".into());
     }
-    Ok(())
+    origin::validate_allow_lists(settings)
 }
 
-fn load_http_settings(app: &tauri::AppHandle) -> HttpSettings {
+fn load_stored_setting<T: DeserializeOwned + Default>(
+    app: &tauri::AppHandle,
+    key: &str,
+    what: &str,
+) -> T {
     let store = match app.store(STORE_FILE) {
         Ok(store) => store,
         Err(err) => {
             eprintln!("Failed to open settings store: {err}");
-            return HttpSettings::default();
+            return T::default();
         }
     };
 
-    match store.get(HTTP_SETTINGS_KEY) {
-        Some(value) => match serde_json::from_value::<HttpSettings>(value.clone()) {
+    match store.get(key) {
+        Some(value) => match serde_json::from_value::<T>(value.clone()) {
             Ok(settings) => settings,
             Err(err) => {
-                eprintln!("Failed to parse stored HTTP settings: {err}");
-                HttpSettings::default()
+                eprintln!("Failed to parse stored {what}: {err}");
+                T::default()
             }
         },
-        None => HttpSettings::default(),
+        None => T::default(),
     }
 }
 
-fn persist_http_settings(app: &tauri::AppHandle, settings: &HttpSettings) -> Result<(), String> {
+fn persist_stored_setting<T: Serialize>(
+    app: &tauri::AppHandle,
+    key: &str,
+    value: &T,
+    what: &str,
+) -> Result<(), String> {
     let store = app
         .store(STORE_FILE)
         .map_err(|err| format!("Failed to open settings store: {err}"))?;
     store.set(
-        HTTP_SETTINGS_KEY,
-        serde_json::to_value(settings)
-            .map_err(|err| format!("Failed to serialize HTTP settings: {err}"))?,
+        key,
+        serde_json::to_value(value).map_err(|err| format!("Failed to serialize {what}: {err}"))?,
     );
     store
         .save()
-        .map_err(|err| format!("Failed to save HTTP settings: {err}"))
+        .map_err(|err| format!("Failed to save {what}: {err}"))
+}
+
+fn load_http_settings(app: &tauri::AppHandle) -> HttpSettings {
+    load_stored_setting(app, HTTP_SETTINGS_KEY, "HTTP settings")
+}
+
+fn persist_http_settings(app: &tauri::AppHandle, settings: &HttpSettings) -> Result<(), String> {
+    persist_stored_setting(app, HTTP_SETTINGS_KEY, settings, "HTTP settings")
 }
 
 fn spawn_http_server(
-    app: tauri::AppHandle,
-    listening: Arc<AtomicBool>,
+    state: AppState,
     settings: HttpSettings,
 ) -> tauri::async_runtime::JoinHandle<()> {
     tauri::async_runtime::spawn(async move {
-        let state = AppState { app, listening };
         let router = Router::new()
             .route("/agent/notify", post(notify_handler))
-            .route("/mcp", post(mcp_post_handler).get(mcp_get_handler))
+            .route("/agent/notifications", get(notifications_handler))
+            .route("/agent/tasks", get(tasks_handler).post(start_task_handler))
+            .route("/agent/tasks/:id/finish", post(finish_task_handler))
+            .route("/agent/tasks/:id/heartbeat", post(task_heartbeat_handler))
+            .route(
+                "/agent/processes",
+                get(process_watches_handler).post(watch_process_handler),
+            )
+            .route("/agent/processes/:id", delete(cancel_process_watch_handler))
+            .route("/agent/scheduled", get(scheduled_notifications_handler))
+            .route(
+                "/agent/scheduled/:id",
+                delete(cancel_scheduled_notification_handler),
+            )
+            .route("/agent/probes", get(probes_handler).post(wait_for_handler))
+            .route("/agent/probes/:id", delete(cancel_probe_handler))
+            .route(
+                "/agent/watches",
+                get(file_watches_handler).post(create_file_watch_handler),
+            )
+            .route("/agent/watches/:id", delete(delete_file_watch_handler))
+            .route(
+                "/agent/notifications/:id",
+                patch(update_notification_handler).delete(dismiss_notification_handler),
+            )
+            .route("/agent/notifications/:id/ack", post(acknowledge_handler))
+            .route("/agent/escalations", get(escalations_handler))
+            .route(
+                "/agent/notifications/:id/actions",
+                get(notification_actions_handler),
+            )
+            .route(
+                "/mcp",
+                post(mcp::post_handler)
+                    .get(mcp::get_handler)
+                    .delete(mcp::delete_handler),
+            )
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                auth::require_bearer_token,
+            ))
+            // Outermost layer: reject rebinding attempts before anything else runs.
+            .layer(middleware::from_fn_with_state(
+                Arc::new(RequestOriginPolicy::from_settings(&settings)),
+                origin::validate_origin_and_host,
+            ))
             .with_state(state);
 
         let bind_addr = format!("{}:{}", settings.bind_address, settings.port);
@@ -537,7 +2613,8 @@ fn spawn_http_server(
             }
         };
 
-        if let Err(err) = axum::serve(listener, router).await {
+        let service = router.into_make_service_with_connect_info::<SocketAddr>();
+        if let Err(err) = axum::serve(listener, service).await {
             eprintln!("HTTP server error: {err}");
         }
     })
@@ -550,16 +2627,32 @@ async fn restart_http_server(app: &tauri::AppHandle, managed: &ManagedState) ->
     if let Some(handle) = guard.take() {
         handle.abort();
     }
-    *guard = Some(spawn_http_server(
-        app.clone(),
-        managed.listening.clone(),
-        settings,
-    ));
+    *guard = Some(spawn_http_server(managed.app_state(app), settings));
     Ok(())
 }
 
+fn show_main_window(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        eprintln!("Main window not found");
+        return;
+    };
+    if let Err(err) = window.show() {
+        eprintln!("Failed to show main window: {err}");
+    }
+    if let Err(err) = window.unminimize() {
+        eprintln!("Failed to unminimize main window: {err}");
+    }
+    if let Err(err) = window.set_focus() {
+        eprintln!("Failed to focus main window: {err}");
+    }
+}
+
 #[cfg(desktop)]
-fn setup_tray(app: &tauri::AppHandle, listening: Arc<AtomicBool>) -> tauri::Result<()> {
+fn setup_tray(
+    app: &tauri::AppHandle,
+    listening: Arc<AtomicBool>,
+    muted: bool,
+) -> tauri::Result<()> {
     let open_item = MenuItem::with_id(app, "open_window", "Settings", true, None::<&str>)?;
     let start_item = MenuItem::with_id(
         app,
@@ -569,6 +2662,9 @@ fn setup_tray(app: &tauri::AppHandle, listening: Arc<AtomicBool>) -> tauri::Resu
         None::<&str>,
     )?;
     let stop_item = MenuItem::with_id(app, "stop_listening", "Stop listening", true, None::<&str>)?;
+    let mute_item =
+        CheckMenuItem::with_id(app, "toggle_mute", "Mute sounds", true, muted, None::<&str>)?;
+    let tasks_menu = Submenu::with_id(app, "running_tasks", "Running tasks", true)?;
 
     if !listening.load(Ordering::SeqCst) {
         // Ensure menu reflects the actual state if we ever start with listening disabled.
@@ -581,6 +2677,13 @@ fn setup_tray(app: &tauri::AppHandle, listening: Arc<AtomicBool>) -> tauri::Resu
         .separator()
         .item(&start_item)
         .item(&stop_item)
+        .item(&mute_item)
+        .separator()
+        .text("pause_30", "Pause for 30 minutes")
+        .text("pause_60", "Pause for 1 hour")
+        .text("resume_notifications", "Resume notifications")
+        .separator()
+        .item(&tasks_menu)
         .separator()
         .text("quit", "Quit")
         .build()?;
@@ -598,23 +2701,14 @@ fn setup_tray(app: &tauri::AppHandle, listening: Arc<AtomicBool>) -> tauri::Resu
     let mut tray_builder = TrayIconBuilder::new().menu(&menu).on_menu_event({
         let start_item = start_item.clone();
         let stop_item = stop_item.clone();
+        let mute_item = mute_item.clone();
         move |app, event| match event.id().as_ref() {
-            "quit" => app.exit(0),
-            "open_window" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    if let Err(err) = window.show() {
-                        eprintln!("Failed to show main window: {err}");
-                    }
-                    if let Err(err) = window.unminimize() {
-                        eprintln!("Failed to unminimize main window: {err}");
-                    }
-                    if let Err(err) = window.set_focus() {
-                        eprintln!("Failed to focus main window: {err}");
-                    }
-                } else {
-                    eprintln!("Main window not found when handling tray 'Settings'");
-                }
+            "quit" => {
+                // The writer may still be waiting to save the latest notifications.
+                app.state::<ManagedState>().history.flush();
+                app.exit(0);
             }
+            "open_window" => show_main_window(app),
             "stop_listening" => {
                 listening.store(false, Ordering::SeqCst);
                 if let Err(err) = stop_item.set_enabled(false) {
@@ -633,6 +2727,19 @@ fn setup_tray(app: &tauri::AppHandle, listening: Arc<AtomicBool>) -> tauri::Resu
                     eprintln!("Failed to enable 'Stop listening' menu item: {err}");
                 }
             }
+            "pause_30" => pause_from_tray(app, Some(30)),
+            "pause_60" => pause_from_tray(app, Some(60)),
+            "resume_notifications" => pause_from_tray(app, None),
+            "toggle_mute" => {
+                // The menu flips the check mark itself before this runs.
+                let muted = mute_item.is_checked().unwrap_or(false);
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(err) = set_sounds_muted(&app, muted).await {
+                        eprintln!("{err}");
+                    }
+                });
+            }
             _ => {}
         }
     });
@@ -644,9 +2751,79 @@ fn setup_tray(app: &tauri::AppHandle, listening: Arc<AtomicBool>) -> tauri::Resu
     }
 
     tray_builder.build(app)?;
+    app.manage(TrayMuteItem(mute_item));
+    app.manage(TrayTasksMenu(tasks_menu));
+    sync_tray_tasks(app, &[]);
     Ok(())
 }
 
+/// Pauses notifications for `minutes`, or resumes them (and delivers the digest) on `None`.
+#[cfg(desktop)]
+fn pause_from_tray(app: &tauri::AppHandle, minutes: Option<u64>) {
+    let state = app.state::<ManagedState>().app_state(app);
+    match minutes {
+        Some(minutes) => state.quiet.pause(unix_millis_now() + minutes * 60 * 1000),
+        None => {
+            state.quiet.resume();
+            tauri::async_runtime::spawn(async move { deliver_quiet_digest(&state).await });
+        }
+    }
+}
+
+#[cfg(desktop)]
+struct TrayMuteItem(CheckMenuItem<tauri::Wry>);
+
+#[cfg(desktop)]
+fn sync_tray_mute(app: &tauri::AppHandle, muted: bool) {
+    if let Some(item) = app.try_state::<TrayMuteItem>() {
+        if let Err(err) = item.0.set_checked(muted) {
+            eprintln!("Failed to update 'Mute sounds' menu item: {err}");
+        }
+    }
+}
+
+#[cfg(desktop)]
+struct TrayTasksMenu(Submenu<tauri::Wry>);
+
+/// Rebuilds the "Running tasks" submenu; entries show when each task started so they never
+/// go stale.
+#[cfg(desktop)]
+fn sync_tray_tasks(app: &tauri::AppHandle, running: &[RunningTask]) {
+    let Some(menu) = app.try_state::<TrayTasksMenu>() else {
+        return;
+    };
+    let menu = &menu.0;
+    let result = (|| -> tauri::Result<()> {
+        while menu.remove_at(0)?.is_some() {}
+        if running.is_empty() {
+            let item = MenuItem::new(app, "No running tasks", false, None::<&str>)?;
+            return menu.append(&item);
+        }
+        for task in running {
+            let since = chrono::DateTime::from_timestamp_millis(task.started_at as i64)
+                .map(|time| {
+                    time.with_timezone(&chrono::Local)
+                        .format("%H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            let warning = if task.stalled {
+                " – no heartbeat"
+            } else if task.overdue {
+                " – overdue"
+            } else {
+                ""
+            };
+            let label = format!("{}: {} (since {since}){warning}", task.agent, task.title);
+            menu.append(&MenuItem::new(app, label, false, None::<&str>)?)?;
+        }
+        Ok(())
+    })();
+    if let Err(err) = result {
+        eprintln!("Failed to update 'Running tasks' menu: {err}");
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -702,27 +2879,139 @@ pub fn run() {
             let listening = Arc::new(AtomicBool::new(true));
             ensure_notification_permission(&app_handle);
             let initial_settings = load_http_settings(&app_handle);
+            let auth_settings: AuthSettings =
+                load_stored_setting(app_handle, auth::AUTH_SETTINGS_KEY, "API tokens");
+            let level_settings: LevelSettings = load_stored_setting(
+                app_handle,
+                levels::LEVELS_SETTINGS_KEY,
+                "notification levels",
+            );
+            let sound_bank = SoundBank::load(
+                load_stored_setting(app_handle, audio::AUDIO_SETTINGS_KEY, "audio settings"),
+                &level_settings,
+            );
+            let player = AudioPlayer::spawn(sound_bank.settings());
+            let muted = sound_bank.settings().muted;
             let managed_state = ManagedState {
                 listening: listening.clone(),
                 server_task: Mutex::new(None),
                 settings: RwLock::new(initial_settings.clone()),
+                history: Arc::new(NotificationHistory::load(app_handle)),
+                auth: Arc::new(RwLock::new(auth_settings)),
+                sessions: Arc::new(SessionRegistry::default()),
+                prompts: Arc::new(PromptRegistry::default()),
+                mcp_tools: Arc::new(RwLock::new(load_stored_setting(
+                    app_handle,
+                    prompts::MCP_SETTINGS_KEY,
+                    "MCP tool settings",
+                ))),
+                levels: Arc::new(RwLock::new(level_settings)),
+                sounds: Arc::new(RwLock::new(sound_bank)),
+                player: Arc::new(player),
+                quiet: Arc::new(QuietHours::new(load_stored_setting(
+                    app_handle,
+                    quiet::QUIET_SETTINGS_KEY,
+                    "quiet hours",
+                ))),
+                rules: Arc::new(RwLock::new(RuleSet::load(load_stored_setting(
+                    app_handle,
+                    rules::RULES_SETTINGS_KEY,
+                    "notification rules",
+                )))),
+                coalescer: Arc::new(Coalescer::new(load_stored_setting(
+                    app_handle,
+                    coalesce::COALESCE_SETTINGS_KEY,
+                    "notification coalescing",
+                ))),
+                limiter: Arc::new(RateLimiter::new(load_stored_setting(
+                    app_handle,
+                    ratelimit::RATE_LIMIT_SETTINGS_KEY,
+                    "rate limits",
+                ))),
+                actions: Arc::new(ActionRegistry::default()),
+                tasks: Arc::new(TaskTracker::default()),
+                processes: Arc::new(ProcessWatcher::default()),
+                files: Arc::new(FileWatcher::new(load_stored_setting(
+                    app_handle,
+                    filewatch::FILE_WATCHES_KEY,
+                    "file watches",
+                ))),
+                probes: Arc::new(ProbeRegistry::default()),
+                scheduled: Arc::new(Scheduler::new(load_stored_setting(
+                    app_handle,
+                    schedule::SCHEDULED_NOTIFICATIONS_KEY,
+                    "scheduled notifications",
+                ))),
+                escalations: Arc::new(Escalations::new(load_stored_setting(
+                    app_handle,
+                    escalation::ESCALATION_SETTINGS_KEY,
+                    "escalation policies",
+                ))),
             };
 
             tauri::async_runtime::block_on(async {
                 let handle =
-                    spawn_http_server(app_handle.clone(), listening.clone(), initial_settings);
+                    spawn_http_server(managed_state.app_state(app_handle), initial_settings);
                 *managed_state.server_task.lock().await = Some(handle);
             });
+            tauri::async_runtime::spawn(watch_quiet_hours(managed_state.app_state(app_handle)));
+            tauri::async_runtime::spawn(watch_bursts(managed_state.app_state(app_handle)));
+            tauri::async_runtime::spawn(watch_tasks(managed_state.app_state(app_handle)));
+            tauri::async_runtime::spawn(watch_processes(managed_state.app_state(app_handle)));
+            tauri::async_runtime::spawn(watch_files(managed_state.app_state(app_handle)));
+            tauri::async_runtime::spawn(watch_schedule(managed_state.app_state(app_handle)));
+            tauri::async_runtime::spawn(watch_escalations(managed_state.app_state(app_handle)));
 
             app.manage(managed_state);
             #[cfg(desktop)]
-            setup_tray(&app_handle, listening)?;
+            setup_tray(&app_handle, listening, muted)?;
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet,
             get_http_bindings,
-            save_http_bindings
+            save_http_bindings,
+            get_notification_history,
+            clear_notification_history,
+            list_api_tokens,
+            create_api_token,
+            revoke_api_token,
+            set_token_auth_required,
+            list_mcp_sessions,
+            list_pending_prompts,
+            answer_prompt,
+            get_mcp_tool_settings,
+            save_mcp_tool_settings,
+            get_level_settings,
+            save_level_settings,
+            list_bundled_sounds,
+            get_audio_settings,
+            save_audio_settings,
+            preview_sound,
+            list_output_devices,
+            get_quiet_settings,
+            save_quiet_settings,
+            get_quiet_status,
+            pause_notifications,
+            resume_notifications,
+            get_notification_rules,
+            save_notification_rules,
+            get_coalesce_settings,
+            save_coalesce_settings,
+            get_rate_limit_settings,
+            save_rate_limit_settings,
+            list_notification_actions,
+            run_notification_action,
+            dismiss_notification_actions,
+            list_file_watches,
+            add_file_watch,
+            remove_file_watch,
+            list_scheduled_notifications,
+            remove_scheduled_notification,
+            get_escalation_settings,
+            save_escalation_settings,
+            list_escalations,
+            acknowledge_notification
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
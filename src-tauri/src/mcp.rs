@@ -0,0 +1,1629 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension, Json,
+};
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tokio_stream::{
+    wrappers::{BroadcastStream, ReceiverStream},
+    StreamExt,
+};
+
+use crate::actions::{self, NotificationAction, MAX_ACTIONS};
+use crate::auth::TokenIdentity;
+use crate::filewatch::FileWatch;
+use crate::history::NotificationSource;
+use crate::jsonrpc::{
+    self, jsonrpc_error, jsonrpc_success, Message, Payload, RpcError, INVALID_REQUEST,
+    RATE_LIMITED, SERVER_ERROR,
+};
+use crate::levels::NotificationLevel;
+use crate::probes::ProbeRequest;
+use crate::procwatch::ProcessWatchRequest;
+use crate::prompts::{PendingPrompt, PromptAnswer};
+use crate::protocol::{self, PROTOCOL_VERSION_HEADER};
+use crate::schedule;
+use crate::session::{self, LogLevel, McpSession, SessionLookup, SESSION_HEADER};
+use crate::tasks::{format_duration, TaskOutcome};
+use crate::{
+    cancel_scheduled_notification, create_file_watch, delete_file_watch, dismiss_notification,
+    dispatch_notification, finish_task, schedule_notification, show_main_window, start_task,
+    task_heartbeat, unix_millis_now, update_notification, validate_notification_fields, wait_for,
+    watch_process, AppState, ChangeError, DispatchStatus, Dispatched, Notification,
+    NotificationUpdate, TaskFinish, TaskStart, UpdateStatus, SOFT_CONTENT_LIMIT_CHARS,
+};
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+const ASK_USER_MAX_CHOICES: usize = 5;
+const ASK_USER_DEFAULT_TIMEOUT_SECS: u64 = 120;
+const ASK_USER_MAX_TIMEOUT_SECS: u64 = 600;
+// How often a streamed `ask_user` call reports that it is still waiting.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Everything a method handler needs to know about the caller.
+struct RequestContext {
+    state: AppState,
+    identity: Option<TokenIdentity>,
+    session: McpSession,
+    client: IpAddr,
+}
+
+fn not_listening() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "message": "Server is not listening" })),
+    )
+        .into_response()
+}
+
+/// Properties shared by the tools that send a notification.
+fn notification_properties() -> Value {
+    json!({
+        "title": { "type": "string", "minLength": 1 },
+        "content": { "type": "string", "minLength": 1, "maxLength": SOFT_CONTENT_LIMIT_CHARS as i64 },
+        "agent": { "type": "string", "minLength": 1 },
+        "level": { "type": "string", "enum": NotificationLevel::NAMES, "default": "info" },
+        "silent": { "type": "boolean", "default": false },
+        "url": { "type": "string", "format": "uri" },
+        "actions": {
+            "type": "array",
+            "maxItems": MAX_ACTIONS,
+            "items": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string" },
+                    "label": { "type": "string", "minLength": 1 },
+                    "type": { "type": "string", "enum": ["open_url", "open_path", "copy", "reply"] },
+                    "url": { "type": "string" },
+                    "path": { "type": "string" },
+                    "text": { "type": "string" },
+                    "allow_text": { "type": "boolean" }
+                },
+                "required": ["label", "type"]
+            }
+        }
+    })
+}
+
+fn notify_tool_descriptor(protocol_version: &str) -> Value {
+    let mut descriptor = json!({
+        "name": "notify",
+        "description": "Send a desktop notification via the Agent Notifications app with title, content, and agent label.",
+        "inputSchema": {
+            "type": "object",
+            "properties": notification_properties(),
+            "required": ["title", "content", "agent"],
+            "additionalProperties": false
+        }
+    });
+
+    if protocol::supports_structured_output(protocol_version) {
+        descriptor["title"] = json!("Send notification");
+        descriptor["outputSchema"] = json!({
+            "type": "object",
+            "properties": {
+                "status": {
+                    "type": "string",
+                    "enum": ["sent", "queued", "muted", "duplicate", "merged"]
+                },
+                "merged": { "type": "boolean" },
+                "id": { "type": "string" },
+                "title": { "type": "string" },
+                "agent": { "type": "string" },
+                "level": { "type": "string" },
+                "rule": {
+                    "type": ["object", "null"],
+                    "properties": {
+                        "id": { "type": "string" },
+                        "name": { "type": "string" }
+                    }
+                }
+            },
+            "required": ["id", "status", "title", "agent", "level", "merged"]
+        });
+    }
+
+    descriptor
+}
+
+fn schedule_notification_tool_descriptor(protocol_version: &str) -> Value {
+    let mut properties = notification_properties();
+    properties["deliver_at"] = json!({
+        "type": "string",
+        "format": "date-time",
+        "description": "When to deliver it, as an RFC 3339 time such as 2025-01-31T17:00:00Z."
+    });
+    properties["delay_seconds"] = json!({
+        "type": "integer",
+        "minimum": 1,
+        "description": "Deliver this many seconds from now instead."
+    });
+    let mut descriptor = json!({
+        "name": "schedule_notification",
+        "description": "Schedule a notification for later, such as a reminder to check a canary in 30 minutes. Pass deliver_at or delay_seconds. Scheduled notifications survive app restarts, and any that fall due while the app is closed are delivered when it starts.",
+        "inputSchema": {
+            "type": "object",
+            "properties": properties,
+            "required": ["title", "content", "agent"],
+            "additionalProperties": false
+        }
+    });
+
+    if protocol::supports_structured_output(protocol_version) {
+        descriptor["title"] = json!("Schedule notification");
+        descriptor["outputSchema"] = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "deliverAt": { "type": "string", "format": "date-time" }
+            },
+            "required": ["id", "deliverAt"]
+        });
+    }
+
+    descriptor
+}
+
+fn cancel_scheduled_notification_tool_descriptor() -> Value {
+    json!({
+        "name": "cancel_scheduled_notification",
+        "description": "Cancel a notification scheduled with schedule_notification before it is delivered.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn update_notification_tool_descriptor(protocol_version: &str) -> Value {
+    let mut descriptor = json!({
+        "name": "update_notification",
+        "description": "Replace the title, content or level of a notification sent with notify, for example to report progress without stacking new notifications. Set alert to show the update as a new desktop notification.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1 },
+                "title": { "type": "string", "minLength": 1 },
+                "content": { "type": "string", "minLength": 1, "maxLength": SOFT_CONTENT_LIMIT_CHARS as i64 },
+                "level": { "type": "string", "enum": NotificationLevel::NAMES },
+                "alert": { "type": "boolean", "default": false },
+                "silent": { "type": "boolean", "default": false }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        }
+    });
+
+    if protocol::supports_structured_output(protocol_version) {
+        descriptor["title"] = json!("Update a notification");
+        descriptor["outputSchema"] = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "status": { "type": "string", "enum": ["updated", "delivered", "queued"] },
+                "title": { "type": "string" },
+                "content": { "type": "string" },
+                "level": { "type": "string" }
+            },
+            "required": ["id", "status", "title", "content", "level"]
+        });
+    }
+
+    descriptor
+}
+
+fn task_started_tool_descriptor() -> Value {
+    json!({
+        "name": "task_started",
+        "description": "Start timing a long-running task. Call task_finished with the same id when it ends; the app works out how long it took and notifies the user. Set deadline_seconds or heartbeat_seconds to be warned if the task overruns or stops calling task_heartbeat.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1, "maxLength": 128 },
+                "title": { "type": "string", "minLength": 1 },
+                "agent": { "type": "string", "minLength": 1 },
+                "deadline_seconds": { "type": "integer", "minimum": 1 },
+                "heartbeat_seconds": { "type": "integer", "minimum": 10 }
+            },
+            "required": ["id", "title", "agent"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn task_heartbeat_tool_descriptor() -> Value {
+    json!({
+        "name": "task_heartbeat",
+        "description": "Report that a task started with heartbeat_seconds is still making progress.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1, "maxLength": 128 },
+                "agent": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id", "agent"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn task_finished_tool_descriptor(protocol_version: &str) -> Value {
+    let mut descriptor = json!({
+        "name": "task_finished",
+        "description": "Finish a task started with task_started. Sends a notification with the outcome and elapsed time, followed by the optional summary.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1, "maxLength": 128 },
+                "agent": { "type": "string", "minLength": 1 },
+                "outcome": { "type": "string", "enum": TaskOutcome::NAMES, "default": "succeeded" },
+                "summary": { "type": "string", "maxLength": SOFT_CONTENT_LIMIT_CHARS as i64 },
+                "level": { "type": "string", "enum": NotificationLevel::NAMES },
+                "silent": { "type": "boolean", "default": false }
+            },
+            "required": ["id", "agent"],
+            "additionalProperties": false
+        }
+    });
+
+    if protocol::supports_structured_output(protocol_version) {
+        descriptor["title"] = json!("Finish a task");
+        descriptor["outputSchema"] = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "agent": { "type": "string" },
+                "title": { "type": "string" },
+                "outcome": { "type": "string", "enum": TaskOutcome::NAMES },
+                "startedAt": { "type": "integer" },
+                "finishedAt": { "type": "integer" },
+                "durationMs": { "type": "integer" },
+                "notificationId": { "type": "string" }
+            },
+            "required": ["id", "agent", "title", "outcome", "startedAt", "finishedAt", "durationMs", "notificationId"]
+        });
+    }
+
+    descriptor
+}
+
+fn watch_process_tool_descriptor(protocol_version: &str) -> Value {
+    let mut descriptor = json!({
+        "name": "watch_process",
+        "description": "Notify the user when a process on this machine exits, such as a build started in another terminal. Pass its pid, or a name matched against process names and then command lines; the name must match exactly one process.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "pid": { "type": "integer", "minimum": 1 },
+                "name": { "type": "string", "minLength": 1 },
+                "agent": { "type": "string", "minLength": 1 },
+                "title": { "type": "string", "minLength": 1 }
+            },
+            "required": ["agent"],
+            "additionalProperties": false
+        }
+    });
+
+    if protocol::supports_structured_output(protocol_version) {
+        descriptor["title"] = json!("Watch a process");
+        descriptor["outputSchema"] = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "pid": { "type": "integer" },
+                "name": { "type": "string" },
+                "command": { "type": "string" }
+            },
+            "required": ["id", "pid", "name", "command"]
+        });
+    }
+
+    descriptor
+}
+
+fn wait_for_tool_descriptor() -> Value {
+    json!({
+        "name": "wait_for",
+        "description": "Notify the user when a TCP port or HTTP URL becomes reachable, such as a dev server starting up, or when it still is not after the timeout. The app polls with backoff in the background, so there is no need to poll yourself. For URLs any response below 500 counts as up unless 'status' is given.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "target": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "host:port, a port on this machine, or an http(s) URL."
+                },
+                "agent": { "type": "string", "minLength": 1 },
+                "title": { "type": "string", "minLength": 1, "description": "What is being waited for, e.g. 'Dev server'." },
+                "timeout_seconds": { "type": "integer", "minimum": 1, "default": 300 },
+                "status": { "type": "integer", "minimum": 100, "maximum": 599 }
+            },
+            "required": ["target", "agent"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn cancel_wait_for_tool_descriptor() -> Value {
+    json!({
+        "name": "cancel_wait_for",
+        "description": "Stop a probe started with wait_for without notifying.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn watch_file_tool_descriptor() -> Value {
+    json!({
+        "name": "watch_file",
+        "description": "Notify the user when a file or directory appears or changes, or when a line appended to a log file matches a regular expression. Watches are kept across app restarts; by default each is removed after it fires once.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "minLength": 1, "description": "Absolute path." },
+                "type": { "type": "string", "enum": ["appears", "changes", "matches"] },
+                "pattern": { "type": "string", "minLength": 1, "description": "Regular expression; required for 'matches'." },
+                "agent": { "type": "string", "minLength": 1 },
+                "title": { "type": "string", "minLength": 1 },
+                "level": { "type": "string", "enum": NotificationLevel::NAMES, "default": "info" },
+                "once": { "type": "boolean", "default": true }
+            },
+            "required": ["path", "type", "agent"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn cancel_file_watch_tool_descriptor() -> Value {
+    json!({
+        "name": "cancel_file_watch",
+        "description": "Remove a watch created with watch_file.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn cancel_process_watch_tool_descriptor() -> Value {
+    json!({
+        "name": "cancel_process_watch",
+        "description": "Stop watching a process started with watch_process.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn dismiss_notification_tool_descriptor() -> Value {
+    json!({
+        "name": "dismiss_notification",
+        "description": "Dismiss a notification sent with notify once it no longer matters. It leaves the Agent Notifications window and can no longer be updated.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        }
+    })
+}
+
+fn ask_user_tool_descriptor(protocol_version: &str) -> Value {
+    let mut descriptor = json!({
+        "name": "ask_user",
+        "description": "Ask the user a question in the Agent Notifications app and wait for their answer. Offer up to five choices, allow free text, or both.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "question": { "type": "string", "minLength": 1, "maxLength": SOFT_CONTENT_LIMIT_CHARS as i64 },
+                "agent": { "type": "string", "minLength": 1 },
+                "choices": {
+                    "type": "array",
+                    "items": { "type": "string", "minLength": 1 },
+                    "maxItems": ASK_USER_MAX_CHOICES
+                },
+                "allow_text": { "type": "boolean", "default": false },
+                "timeout_seconds": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": ASK_USER_MAX_TIMEOUT_SECS,
+                    "default": ASK_USER_DEFAULT_TIMEOUT_SECS
+                }
+            },
+            "required": ["question", "agent"],
+            "additionalProperties": false
+        }
+    });
+
+    if protocol::supports_structured_output(protocol_version) {
+        descriptor["title"] = json!("Ask the user");
+        descriptor["outputSchema"] = json!({
+            "type": "object",
+            "properties": {
+                "status": { "type": "string", "enum": ["answered", "timeout"] },
+                "choice": { "type": ["string", "null"] },
+                "text": { "type": ["string", "null"] }
+            },
+            "required": ["status"]
+        });
+    }
+
+    descriptor
+}
+
+/// Resolves the `Mcp-Session-Id` header, answering 400 when it is missing and 404 when the
+/// session is unknown or expired so the client knows to re-initialize.
+async fn require_session(state: &AppState, headers: &HeaderMap) -> Result<McpSession, Response> {
+    let session_id = headers
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    let session = match state.sessions.touch(session_id).await {
+        SessionLookup::Active(session) => session,
+        SessionLookup::Missing => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(jsonrpc_error(
+                    None,
+                    SERVER_ERROR,
+                    "Bad Request: Mcp-Session-Id header is required",
+                )),
+            )
+                .into_response())
+        }
+        SessionLookup::Unknown => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(jsonrpc_error(None, -32001, "Session not found")),
+            )
+                .into_response())
+        }
+    };
+
+    // The header is optional, but when present it must match what `initialize` negotiated.
+    if let Some(version) = headers.get(PROTOCOL_VERSION_HEADER) {
+        let version = version.to_str().unwrap_or_default();
+        if !protocol::is_supported(version) || version != session.protocol_version {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(jsonrpc_error(
+                    None,
+                    SERVER_ERROR,
+                    &format!("Bad Request: unsupported MCP-Protocol-Version '{version}'"),
+                )),
+            )
+                .into_response());
+        }
+    }
+
+    Ok(session)
+}
+
+async fn initialize(state: &AppState, id: Value, params: Option<&Value>) -> Response {
+    let negotiated = protocol::negotiate(
+        params
+            .and_then(|params| params.get("protocolVersion"))
+            .and_then(Value::as_str),
+    );
+    let session = state
+        .sessions
+        .create(
+            params.and_then(|params| params.get("clientInfo")),
+            negotiated,
+        )
+        .await;
+    let result = json!({
+        "protocolVersion": negotiated,
+        "serverInfo": {
+            "name": "agent-notifications",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "capabilities": {
+            "tools": { "listChanged": true },
+            "logging": {}
+        }
+    });
+
+    (
+        StatusCode::OK,
+        [(SESSION_HEADER, session.id)],
+        Json(jsonrpc_success(id, result)),
+    )
+        .into_response()
+}
+
+async fn handle_message(ctx: &RequestContext, message: Message) -> Option<Value> {
+    match message {
+        Message::Request { id, method, params } => {
+            Some(match handle_request(ctx, &method, params.as_ref()).await {
+                Ok(result) => jsonrpc_success(id, result),
+                Err(err) => err.into_response(id),
+            })
+        }
+        // Notifications and replies need no answer.
+        Message::Notification | Message::Response => None,
+        Message::Invalid { id, reason } => Some(jsonrpc_error(Some(id), INVALID_REQUEST, reason)),
+    }
+}
+
+async fn handle_request(
+    ctx: &RequestContext,
+    method: &str,
+    params: Option<&Value>,
+) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Err(RpcError::new(
+            INVALID_REQUEST,
+            "Invalid Request: initialize must be sent on its own",
+        )),
+        "tools/list" => {
+            let version = &ctx.session.protocol_version;
+            let mut tools = vec![
+                notify_tool_descriptor(version),
+                update_notification_tool_descriptor(version),
+                dismiss_notification_tool_descriptor(),
+                schedule_notification_tool_descriptor(version),
+                cancel_scheduled_notification_tool_descriptor(),
+                task_started_tool_descriptor(),
+                task_heartbeat_tool_descriptor(),
+                task_finished_tool_descriptor(version),
+                watch_process_tool_descriptor(version),
+                cancel_process_watch_tool_descriptor(),
+                wait_for_tool_descriptor(),
+                cancel_wait_for_tool_descriptor(),
+                watch_file_tool_descriptor(),
+                cancel_file_watch_tool_descriptor(),
+            ];
+            if ctx.state.mcp_tools.read().await.ask_user_enabled {
+                tools.push(ask_user_tool_descriptor(version));
+            }
+            Ok(json!({ "tools": tools, "nextCursor": Value::Null }))
+        }
+        "tools/call" => call_tool(ctx, params).await,
+        "logging/setLevel" => {
+            let level = params
+                .and_then(|params| params.get("level"))
+                .cloned()
+                .and_then(|level| serde_json::from_value::<LogLevel>(level).ok())
+                .ok_or_else(|| RpcError::invalid_params("'level' must be a valid log level"))?;
+            ctx.state
+                .sessions
+                .set_log_level(&ctx.session.id, level)
+                .await;
+            Ok(json!({}))
+        }
+        _ => Err(RpcError::method_not_found()),
+    }
+}
+
+type Arguments = serde_json::Map<String, Value>;
+
+async fn call_tool(ctx: &RequestContext, params: Option<&Value>) -> Result<Value, RpcError> {
+    let param_obj = params
+        .and_then(Value::as_object)
+        .ok_or_else(|| RpcError::invalid_params("expected object"))?;
+
+    let tool_name = param_obj
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::invalid_params("missing tool name"))?;
+
+    let arguments = param_obj
+        .get("arguments")
+        .and_then(Value::as_object)
+        .ok_or_else(|| RpcError::invalid_params("'arguments' must be an object"))?;
+
+    match tool_name {
+        "notify" => notify_tool(ctx, arguments).await,
+        "update_notification" => update_notification_tool(ctx, arguments).await,
+        "dismiss_notification" => dismiss_notification_tool(ctx, arguments),
+        "schedule_notification" => schedule_notification_tool(ctx, arguments),
+        "cancel_scheduled_notification" => cancel_scheduled_notification_tool(ctx, arguments),
+        "task_started" => task_started_tool(ctx, arguments),
+        "task_heartbeat" => task_heartbeat_tool(ctx, arguments),
+        "task_finished" => task_finished_tool(ctx, arguments).await,
+        "watch_process" => watch_process_tool(ctx, arguments).await,
+        "cancel_process_watch" => cancel_process_watch_tool(ctx, arguments),
+        "wait_for" => wait_for_tool(ctx, arguments),
+        "cancel_wait_for" => cancel_wait_for_tool(ctx, arguments),
+        "watch_file" => watch_file_tool(ctx, arguments),
+        "cancel_file_watch" => cancel_file_watch_tool(ctx, arguments),
+        "ask_user" if ctx.state.mcp_tools.read().await.ask_user_enabled => {
+            ask_user_tool(ctx, arguments).await
+        }
+        _ => Err(RpcError::new(jsonrpc::METHOD_NOT_FOUND, "Tool not found")),
+    }
+}
+
+fn string_argument<'a>(arguments: &'a Arguments, name: &str) -> &'a str {
+    arguments
+        .get(name)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+/// Reads an optional boolean argument, treating a missing one as false.
+fn bool_argument(arguments: &Arguments, name: &str) -> Result<bool, RpcError> {
+    match arguments.get(name) {
+        None | Some(Value::Null) => Ok(false),
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| RpcError::invalid_params(format!("'{name}' must be a boolean"))),
+    }
+}
+
+/// Applies the bearer token's agent policy to the agent named in the arguments.
+fn resolve_agent(ctx: &RequestContext, arguments: &Arguments) -> Result<String, RpcError> {
+    let agent = string_argument(arguments, "agent");
+    match &ctx.identity {
+        Some(identity) => identity
+            .resolve_agent(agent)
+            .map_err(RpcError::invalid_params),
+        None => Ok(agent.to_owned()),
+    }
+}
+
+/// Builds a notification from notify-style arguments and checks the rate limit.
+fn notification_argument(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Notification, RpcError> {
+    let field = |name: &str| string_argument(arguments, name);
+    let agent = resolve_agent(ctx, arguments)?;
+
+    let (title, content, agent) =
+        validate_notification_fields(field("title"), field("content"), &agent).map_err(|_| {
+            RpcError::invalid_params(
+                "'title', 'content', and 'agent' are required and must be within limits",
+            )
+        })?;
+    let level = match arguments.get("level") {
+        None | Some(Value::Null) => NotificationLevel::default(),
+        Some(value) => serde_json::from_value(value.clone()).map_err(|_| {
+            RpcError::invalid_params(format!(
+                "'level' must be one of {}",
+                NotificationLevel::NAMES.join(", ")
+            ))
+        })?,
+    };
+
+    let silent = bool_argument(arguments, "silent")?;
+    let url = match arguments.get("url") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(url)) => {
+            actions::validate_url(url).map_err(RpcError::invalid_params)?;
+            Some(url.clone())
+        }
+        Some(_) => return Err(RpcError::invalid_params("'url' must be a string")),
+    };
+    let mut notification_actions: Vec<NotificationAction> = match arguments.get("actions") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|err| RpcError::invalid_params(format!("'actions' is not valid: {err}")))?,
+    };
+    actions::validate_actions(&mut notification_actions).map_err(RpcError::invalid_params)?;
+    check_rate_limit(ctx, &agent)?;
+
+    Ok(Notification {
+        title,
+        content,
+        agent,
+        level,
+        silent,
+        source: NotificationSource::Mcp,
+        url,
+        actions: notification_actions,
+        reply_to: Some(ctx.session.id.clone()),
+    })
+}
+
+/// Counts a call that alerts the user against the same limits as `/agent/notify`.
+fn check_rate_limit(ctx: &RequestContext, agent: &str) -> Result<(), RpcError> {
+    ctx.state
+        .limiter
+        .check(agent, ctx.client)
+        .map_err(|limited| {
+            RpcError::new(RATE_LIMITED, limited.message()).with_data(json!({
+                "scope": limited.scope,
+                "retryAfterSeconds": limited.retry_after_secs()
+            }))
+        })
+}
+
+async fn notify_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let notification = notification_argument(ctx, arguments)?;
+    let Dispatched { id, status, rule } =
+        match dispatch_notification(&ctx.state, &notification).await {
+            Ok(dispatched) => dispatched,
+            Err(err) => {
+                eprintln!("{err}");
+                ctx.state
+                    .sessions
+                    .log(&ctx.session.id, LogLevel::Error, json!(err))
+                    .await;
+                return Err(RpcError::new(
+                    SERVER_ERROR,
+                    "Failed to dispatch notification",
+                ));
+            }
+        };
+    let Notification {
+        title,
+        agent,
+        level,
+        ..
+    } = &notification;
+    ctx.state
+        .sessions
+        .log(
+            &ctx.session.id,
+            LogLevel::Debug,
+            json!(format!("Delivered notification '{title}' from {agent}")),
+        )
+        .await;
+
+    let merged = status.is_merged();
+    let (status, text) = match status {
+        DispatchStatus::Delivered => ("sent", format!("Notification sent: {title}")),
+        DispatchStatus::Queued => (
+            "queued",
+            format!("Notification queued until Do Not Disturb ends: {title}"),
+        ),
+        DispatchStatus::Muted => (
+            "muted",
+            format!(
+                "Notification muted by rule '{}': {title}",
+                rule.as_ref().map_or("", |rule| rule.name.as_str())
+            ),
+        ),
+        DispatchStatus::Duplicate => (
+            "duplicate",
+            format!("Duplicate of a recent notification, not shown again: {title}"),
+        ),
+        DispatchStatus::Merged => (
+            "merged",
+            format!("Notification merged into a summary of recent updates: {title}"),
+        ),
+    };
+    // The id is what update_notification and dismiss_notification take.
+    let text = format!("{text} (id {id})");
+    let mut result = json!({
+        "content": [
+            {
+                "type": "text",
+                "text": text
+            }
+        ],
+        "isError": false
+    });
+    if protocol::supports_structured_output(&ctx.session.protocol_version) {
+        result["structuredContent"] = json!({
+            "id": id,
+            "status": status,
+            "title": title,
+            "agent": agent,
+            "level": level,
+            "merged": merged,
+            "rule": rule
+        });
+    }
+
+    Ok(result)
+}
+
+/// The agent a bearer token limits this session to, if any.
+fn owner(ctx: &RequestContext) -> Option<&str> {
+    ctx.identity
+        .as_ref()
+        .map(|identity| identity.label.as_str())
+}
+
+fn change_error(err: ChangeError) -> RpcError {
+    match err {
+        ChangeError::Limited(limited) => {
+            RpcError::new(RATE_LIMITED, limited.message()).with_data(json!({
+                "scope": limited.scope,
+                "retryAfterSeconds": limited.retry_after_secs()
+            }))
+        }
+        ChangeError::Failed(err) => {
+            eprintln!("{err}");
+            RpcError::new(SERVER_ERROR, "Failed to dispatch notification")
+        }
+        err => RpcError::invalid_params(err.message()),
+    }
+}
+
+/// Reads an optional string argument, treating null as missing.
+fn optional_string_argument(arguments: &Arguments, name: &str) -> Result<Option<String>, RpcError> {
+    match arguments.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(RpcError::invalid_params(format!(
+            "'{name}' must be a string"
+        ))),
+    }
+}
+
+/// Reads an optional non-negative integer argument, treating null as missing.
+fn optional_u64_argument(arguments: &Arguments, name: &str) -> Result<Option<u64>, RpcError> {
+    match arguments.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| {
+            RpcError::invalid_params(format!("'{name}' must be a non-negative integer"))
+        }),
+    }
+}
+
+async fn update_notification_tool(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Value, RpcError> {
+    let id = string_argument(arguments, "id");
+    let level = match arguments.get("level") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(serde_json::from_value(value.clone()).map_err(|_| {
+            RpcError::invalid_params(format!(
+                "'level' must be one of {}",
+                NotificationLevel::NAMES.join(", ")
+            ))
+        })?),
+    };
+    let update = NotificationUpdate {
+        title: optional_string_argument(arguments, "title")?,
+        content: optional_string_argument(arguments, "content")?,
+        level,
+        alert: bool_argument(arguments, "alert")?,
+        silent: bool_argument(arguments, "silent")?,
+    };
+
+    let (record, status) = update_notification(&ctx.state, id, update, owner(ctx), ctx.client)
+        .await
+        .map_err(change_error)?;
+
+    let text = match status {
+        UpdateStatus::Updated => format!("Notification updated: {}", record.title),
+        UpdateStatus::Delivered => format!("Notification updated and shown: {}", record.title),
+        UpdateStatus::Queued => format!(
+            "Notification updated and queued until Do Not Disturb ends: {}",
+            record.title
+        ),
+    };
+    let mut result = json!({
+        "content": [
+            {
+                "type": "text",
+                "text": text
+            }
+        ],
+        "isError": false
+    });
+    if protocol::supports_structured_output(&ctx.session.protocol_version) {
+        result["structuredContent"] = json!({
+            "id": record.id,
+            "status": status.as_str(),
+            "title": record.title,
+            "content": record.content,
+            "level": record.level
+        });
+    }
+
+    Ok(result)
+}
+
+fn dismiss_notification_tool(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Value, RpcError> {
+    let id = string_argument(arguments, "id");
+    dismiss_notification(&ctx.state, id, owner(ctx)).map_err(change_error)?;
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("Notification {id} dismissed")
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn task_started_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let start = TaskStart {
+        id: string_argument(arguments, "id").to_owned(),
+        title: string_argument(arguments, "title").to_owned(),
+        agent: resolve_agent(ctx, arguments)?,
+        deadline_seconds: optional_u64_argument(arguments, "deadline_seconds")?,
+        heartbeat_seconds: optional_u64_argument(arguments, "heartbeat_seconds")?,
+    };
+    let (task, restarted) =
+        start_task(&ctx.state, start, NotificationSource::Mcp).map_err(RpcError::invalid_params)?;
+
+    let text = if restarted {
+        format!("Task '{}' restarted", task.id)
+    } else {
+        format!("Task '{}' started", task.id)
+    };
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": text
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn task_heartbeat_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let agent = resolve_agent(ctx, arguments)?;
+    let task = task_heartbeat(&ctx.state, string_argument(arguments, "id"), &agent)
+        .map_err(change_error)?;
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("Heartbeat recorded for task '{}'", task.id)
+            }
+        ],
+        "isError": false
+    }))
+}
+
+async fn task_finished_tool(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Value, RpcError> {
+    let agent = resolve_agent(ctx, arguments)?;
+    let outcome = match arguments.get("outcome") {
+        None | Some(Value::Null) => TaskOutcome::default(),
+        Some(value) => serde_json::from_value(value.clone()).map_err(|_| {
+            RpcError::invalid_params(format!(
+                "'outcome' must be one of {}",
+                TaskOutcome::NAMES.join(", ")
+            ))
+        })?,
+    };
+    let level = match arguments.get("level") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(serde_json::from_value(value.clone()).map_err(|_| {
+            RpcError::invalid_params(format!(
+                "'level' must be one of {}",
+                NotificationLevel::NAMES.join(", ")
+            ))
+        })?),
+    };
+    let finish = TaskFinish {
+        outcome,
+        summary: optional_string_argument(arguments, "summary")?,
+        level,
+        silent: bool_argument(arguments, "silent")?,
+    };
+
+    let (finished, dispatched) = finish_task(
+        &ctx.state,
+        string_argument(arguments, "id"),
+        &agent,
+        finish,
+        NotificationSource::Mcp,
+        ctx.client,
+    )
+    .await
+    .map_err(change_error)?;
+
+    let mut result = json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!(
+                    "Task '{}' {} after {} (notification {})",
+                    finished.task.id,
+                    outcome_word(finished.outcome),
+                    format_duration(finished.duration_ms),
+                    dispatched.id
+                )
+            }
+        ],
+        "isError": false
+    });
+    if protocol::supports_structured_output(&ctx.session.protocol_version) {
+        result["structuredContent"] = json!({
+            "id": finished.task.id,
+            "agent": finished.task.agent,
+            "title": finished.task.title,
+            "outcome": finished.outcome,
+            "startedAt": finished.task.started_at,
+            "finishedAt": finished.finished_at,
+            "durationMs": finished.duration_ms,
+            "notificationId": dispatched.id
+        });
+    }
+
+    Ok(result)
+}
+
+async fn watch_process_tool(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Value, RpcError> {
+    let pid = match optional_u64_argument(arguments, "pid")? {
+        Some(pid) => Some(
+            u32::try_from(pid).map_err(|_| RpcError::invalid_params("'pid' is out of range"))?,
+        ),
+        None => None,
+    };
+    let request = ProcessWatchRequest {
+        pid,
+        name: optional_string_argument(arguments, "name")?,
+        agent: resolve_agent(ctx, arguments)?,
+        title: optional_string_argument(arguments, "title")?,
+    };
+    let watch = watch_process(&ctx.state, request, NotificationSource::Mcp)
+        .await
+        .map_err(RpcError::invalid_params)?;
+
+    let mut result = json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!(
+                    "Watching PID {} ({}); the user is notified when it exits (watch {})",
+                    watch.pid, watch.command, watch.id
+                )
+            }
+        ],
+        "isError": false
+    });
+    if protocol::supports_structured_output(&ctx.session.protocol_version) {
+        result["structuredContent"] = json!({
+            "id": watch.id,
+            "pid": watch.pid,
+            "name": watch.name,
+            "command": watch.command
+        });
+    }
+
+    Ok(result)
+}
+
+fn cancel_process_watch_tool(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Value, RpcError> {
+    let id = string_argument(arguments, "id");
+    let watch = ctx
+        .state
+        .processes
+        .cancel(id, owner(ctx))
+        .ok_or_else(|| RpcError::invalid_params("No process watch with that id"))?;
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("Stopped watching PID {}", watch.pid)
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn schedule_notification_tool(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Value, RpcError> {
+    let deliver_at = schedule::delivery_time(
+        optional_string_argument(arguments, "deliver_at")?.as_deref(),
+        optional_u64_argument(arguments, "delay_seconds")?,
+        unix_millis_now(),
+    )
+    .map_err(RpcError::invalid_params)?
+    .ok_or_else(|| RpcError::invalid_params("Pass 'deliver_at' or 'delay_seconds'"))?;
+    let notification = notification_argument(ctx, arguments)?;
+    let scheduled = schedule_notification(&ctx.state, notification, deliver_at).map_err(|err| {
+        eprintln!("{err}");
+        RpcError::new(SERVER_ERROR, "Failed to schedule notification")
+    })?;
+    let deliver_at = chrono::DateTime::from_timestamp_millis(scheduled.deliver_at as i64)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    let mut result = json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!(
+                    "Scheduled '{}' for {deliver_at} (id {})",
+                    scheduled.title, scheduled.id
+                )
+            }
+        ],
+        "isError": false
+    });
+    if protocol::supports_structured_output(&ctx.session.protocol_version) {
+        result["structuredContent"] = json!({
+            "id": scheduled.id,
+            "deliverAt": deliver_at
+        });
+    }
+
+    Ok(result)
+}
+
+fn cancel_scheduled_notification_tool(
+    ctx: &RequestContext,
+    arguments: &Arguments,
+) -> Result<Value, RpcError> {
+    let id = string_argument(arguments, "id");
+    let scheduled = cancel_scheduled_notification(&ctx.state, id, owner(ctx))
+        .map_err(|err| {
+            eprintln!("{err}");
+            RpcError::new(SERVER_ERROR, "Failed to save scheduled notifications")
+        })?
+        .ok_or_else(|| RpcError::invalid_params("No scheduled notification with that id"))?;
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("Cancelled '{}'", scheduled.title)
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn wait_for_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let status = match optional_u64_argument(arguments, "status")? {
+        Some(status) => Some(
+            u16::try_from(status)
+                .map_err(|_| RpcError::invalid_params("'status' must be an HTTP status code"))?,
+        ),
+        None => None,
+    };
+    let request = ProbeRequest {
+        target: string_argument(arguments, "target").to_owned(),
+        agent: resolve_agent(ctx, arguments)?,
+        title: optional_string_argument(arguments, "title")?,
+        timeout_seconds: optional_u64_argument(arguments, "timeout_seconds")?,
+        status,
+    };
+    let probe =
+        wait_for(&ctx.state, request, NotificationSource::Mcp).map_err(RpcError::invalid_params)?;
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!(
+                    "Waiting for {} for up to {}; the user is notified either way (probe {})",
+                    probe.target,
+                    format_duration(probe.deadline_at - probe.started_at),
+                    probe.id
+                )
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn cancel_wait_for_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let id = string_argument(arguments, "id");
+    let probe = ctx
+        .state
+        .probes
+        .cancel(id, owner(ctx))
+        .ok_or_else(|| RpcError::invalid_params("No probe with that id"))?;
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("Stopped waiting for {}", probe.target)
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn watch_file_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let mut fields = arguments.clone();
+    fields.insert("agent".into(), json!(resolve_agent(ctx, arguments)?));
+    let mut watch: FileWatch = serde_json::from_value(Value::Object(fields))
+        .map_err(|err| RpcError::invalid_params(format!("Invalid watch: {err}")))?;
+    watch.source = NotificationSource::Mcp;
+    let watch = create_file_watch(&ctx.state, watch).map_err(RpcError::invalid_params)?;
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("Watching {} (watch {})", watch.path, watch.id)
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn cancel_file_watch_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let id = string_argument(arguments, "id");
+    let watch = delete_file_watch(&ctx.state, id, owner(ctx))
+        .map_err(|err| {
+            eprintln!("{err}");
+            RpcError::new(SERVER_ERROR, "Failed to save file watches")
+        })?
+        .ok_or_else(|| RpcError::invalid_params("No file watch with that id"))?;
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("Stopped watching {}", watch.path)
+            }
+        ],
+        "isError": false
+    }))
+}
+
+fn outcome_word(outcome: TaskOutcome) -> &'static str {
+    match outcome {
+        TaskOutcome::Succeeded => "succeeded",
+        TaskOutcome::Failed => "failed",
+        TaskOutcome::Cancelled => "was cancelled",
+    }
+}
+
+async fn ask_user_tool(ctx: &RequestContext, arguments: &Arguments) -> Result<Value, RpcError> {
+    let agent = resolve_agent(ctx, arguments)?;
+    let (_, question, agent) = validate_notification_fields(
+        "Input needed",
+        string_argument(arguments, "question"),
+        &agent,
+    )
+    .map_err(|_| {
+        RpcError::invalid_params("'question' and 'agent' are required and must be within limits")
+    })?;
+
+    let choices: Vec<String> = match arguments.get("choices") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(value) => serde_json::from_value::<Vec<String>>(value.clone())
+            .map_err(|_| RpcError::invalid_params("'choices' must be an array of strings"))?
+            .into_iter()
+            .map(|choice| choice.trim().to_owned())
+            .filter(|choice| !choice.is_empty())
+            .collect(),
+    };
+    if choices.len() > ASK_USER_MAX_CHOICES {
+        return Err(RpcError::invalid_params(format!(
+            "at most {ASK_USER_MAX_CHOICES} choices are supported"
+        )));
+    }
+    let allow_text = bool_argument(arguments, "allow_text")?;
+    if choices.is_empty() && !allow_text {
+        return Err(RpcError::invalid_params(
+            "provide 'choices', set 'allow_text', or both",
+        ));
+    }
+    let timeout_secs = match arguments.get("timeout_seconds") {
+        None | Some(Value::Null) => ASK_USER_DEFAULT_TIMEOUT_SECS,
+        Some(value) => value
+            .as_u64()
+            .filter(|secs| (1..=ASK_USER_MAX_TIMEOUT_SECS).contains(secs))
+            .ok_or_else(|| {
+                RpcError::invalid_params(format!(
+                    "'timeout_seconds' must be between 1 and {ASK_USER_MAX_TIMEOUT_SECS}"
+                ))
+            })?,
+    };
+    // Each question shows a toast and plays a sound, so it counts like a notification.
+    check_rate_limit(ctx, &agent)?;
+
+    let now = crate::unix_millis_now();
+    let prompt = PendingPrompt {
+        id: uuid::Uuid::new_v4().to_string(),
+        agent: agent.clone(),
+        question: question.clone(),
+        choices,
+        allow_text,
+        created_at: now,
+        expires_at: now + timeout_secs * 1000,
+    };
+    // The guard withdraws the prompt however this call ends, including the client hanging up.
+    let (_open, answer) = ctx.state.prompts.open(&ctx.state.app, prompt);
+    show_main_window(&ctx.state.app);
+    // The toast only draws attention; the prompt in the window is what collects the answer.
+    let notification = Notification {
+        title: "Input needed".into(),
+        content: question,
+        agent: agent.clone(),
+        level: NotificationLevel::Warning,
+        silent: false,
+        source: NotificationSource::Mcp,
+        url: None,
+        actions: Vec::new(),
+        reply_to: None,
+    };
+    if let Err(err) = dispatch_notification(&ctx.state, &notification).await {
+        eprintln!("{err}");
+    }
+
+    let answer = match tokio::time::timeout(Duration::from_secs(timeout_secs), answer).await {
+        Ok(Ok(answer)) => answer,
+        // The sender only goes away without answering if the prompt was torn down.
+        Ok(Err(_)) | Err(_) => return Ok(ask_user_timeout(ctx, timeout_secs)),
+    };
+    let PromptAnswer { choice, text } = answer;
+    let summary = match (&choice, &text) {
+        (Some(choice), Some(text)) => format!("{choice}: {text}"),
+        (Some(choice), None) => choice.clone(),
+        (None, Some(text)) => text.clone(),
+        (None, None) => String::new(),
+    };
+    ctx.state
+        .sessions
+        .log(
+            &ctx.session.id,
+            LogLevel::Info,
+            json!(format!("User answered {agent}: {summary}")),
+        )
+        .await;
+
+    let mut result = json!({
+        "content": [{ "type": "text", "text": summary }],
+        "isError": false
+    });
+    if protocol::supports_structured_output(&ctx.session.protocol_version) {
+        result["structuredContent"] = json!({
+            "status": "answered",
+            "choice": choice,
+            "text": text
+        });
+    }
+    Ok(result)
+}
+
+fn ask_user_timeout(ctx: &RequestContext, timeout_secs: u64) -> Value {
+    let mut result = json!({
+        "content": [{
+            "type": "text",
+            "text": format!("No answer from the user within {timeout_secs} seconds")
+        }],
+        "isError": true
+    });
+    if protocol::supports_structured_output(&ctx.session.protocol_version) {
+        result["structuredContent"] = json!({
+            "status": "timeout",
+            "choice": Value::Null,
+            "text": Value::Null
+        });
+    }
+    result
+}
+
+/// Whether a lone request should be answered over SSE rather than as a single JSON body.
+///
+/// Only `ask_user` can block for minutes, and only clients that accept `text/event-stream`
+/// get the stream; everyone else simply waits for the JSON reply.
+fn wants_stream(headers: &HeaderMap, message: &Message) -> bool {
+    let Message::Request { method, params, .. } = message else {
+        return false;
+    };
+    let accepts_stream = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+    let tool = params
+        .as_ref()
+        .and_then(|params| params.get("name"))
+        .and_then(Value::as_str);
+
+    accepts_stream && method == "tools/call" && tool == Some("ask_user")
+}
+
+/// Answers a request over SSE, sending `notifications/progress` while it is still waiting if
+/// the client supplied a progress token, then the response itself.
+fn stream_response(ctx: RequestContext, message: Message) -> Response {
+    let progress_token = match &message {
+        Message::Request { params, .. } => params
+            .as_ref()
+            .and_then(|params| params.pointer("/_meta/progressToken"))
+            .cloned(),
+        _ => None,
+    };
+    let (sender, receiver) = mpsc::channel::<Value>(8);
+
+    tokio::spawn(async move {
+        let reply = handle_message(&ctx, message);
+        tokio::pin!(reply);
+        let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+        // The first tick fires immediately; there is nothing to report yet.
+        ticker.tick().await;
+        let mut elapsed = 0;
+
+        let reply = loop {
+            tokio::select! {
+                reply = &mut reply => break reply,
+                // Dropping the pending call withdraws the prompt.
+                _ = sender.closed() => return,
+                _ = ticker.tick() => {
+                    elapsed += PROGRESS_INTERVAL.as_secs();
+                    if let Some(token) = &progress_token {
+                        let progress = session::notification(
+                            "notifications/progress",
+                            json!({
+                                "progressToken": token,
+                                "progress": elapsed,
+                                "message": "Waiting for the user to answer"
+                            }),
+                        );
+                        let _ = sender.send(progress).await;
+                    }
+                }
+            }
+        };
+        if let Some(reply) = reply {
+            let _ = sender.send(reply).await;
+        }
+    });
+
+    let stream = ReceiverStream::new(receiver)
+        .map(|message| Ok::<Event, Infallible>(Event::default().data(message.to_string())));
+    Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(25)))
+        .into_response()
+}
+
+pub(crate) async fn post_handler(
+    State(state): State<AppState>,
+    identity: Option<Extension<TokenIdentity>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return not_listening();
+    }
+
+    // Read the body ourselves so malformed JSON gets a JSON-RPC error instead of axum's text reply.
+    let payload = match jsonrpc::parse(&body) {
+        Ok(payload) => payload,
+        Err(error) => return (StatusCode::BAD_REQUEST, Json(error)).into_response(),
+    };
+
+    match &payload {
+        // `initialize` starts a session, so it is the only request allowed without one.
+        Payload::Single(Message::Request { id, method, params }) if method == "initialize" => {
+            return initialize(&state, id.clone(), params.as_ref()).await;
+        }
+        Payload::Single(Message::Invalid { id, reason }) => {
+            return (
+                StatusCode::OK,
+                Json(jsonrpc_error(Some(id.clone()), INVALID_REQUEST, reason)),
+            )
+                .into_response();
+        }
+        _ => {}
+    }
+
+    let session = match require_session(&state, &headers).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+    let ctx = RequestContext {
+        state,
+        identity: identity.map(|Extension(identity)| identity),
+        session,
+        client: client.ip(),
+    };
+
+    let responses = match payload {
+        Payload::Single(message) if wants_stream(&headers, &message) => {
+            return stream_response(ctx, message);
+        }
+        Payload::Single(message) => match handle_message(&ctx, message).await {
+            Some(response) => return (StatusCode::OK, Json(response)).into_response(),
+            None => Vec::new(),
+        },
+        Payload::Batch(messages) => {
+            let mut responses = Vec::with_capacity(messages.len());
+            for message in messages {
+                responses.extend(handle_message(&ctx, message).await);
+            }
+            responses
+        }
+    };
+
+    // A batch of only notifications and replies is acknowledged without a body.
+    if responses.is_empty() {
+        StatusCode::ACCEPTED.into_response()
+    } else {
+        (StatusCode::OK, Json(Value::Array(responses))).into_response()
+    }
+}
+
+pub(crate) async fn get_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if !state.listening.load(Ordering::SeqCst) {
+        return not_listening();
+    }
+
+    let session = match require_session(&state, &headers).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+
+    // Clients reconnecting after a drop send the last id they saw; replay what they missed.
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let Some((replay, receiver, cursor)) =
+        state.sessions.subscribe(&session.id, last_event_id).await
+    else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(jsonrpc_error(None, -32001, "Session not found")),
+        )
+            .into_response();
+    };
+
+    // A lagging receiver skips events; the client can recover them by reconnecting.
+    let live = BroadcastStream::new(receiver).filter_map(Result::ok);
+    let stream = tokio_stream::iter(replay).chain(live).map(move |event| {
+        cursor.delivered(event.id);
+        Ok::<Event, Infallible>(
+            Event::default()
+                .id(event.id.to_string())
+                .data(event.message.to_string()),
+        )
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(25)))
+        .into_response()
+}
+
+pub(crate) async fn delete_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> StatusCode {
+    let Some(session_id) = headers
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    if state.sessions.remove(session_id).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
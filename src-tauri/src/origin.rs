@@ -0,0 +1,155 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+use crate::HttpSettings;
+
+/// Wildcard entry that disables a check entirely.
+const ALLOW_ANY: &str = "*";
+
+/// Origin and Host allow-lists derived from [`HttpSettings`].
+///
+/// Loopback origins and hosts are always accepted so local agents keep working; anything
+/// else has to be listed explicitly. This blocks DNS-rebinding pages from driving the server.
+pub(crate) struct RequestOriginPolicy {
+    allowed_origins: Vec<String>,
+    allowed_hosts: Vec<String>,
+}
+
+impl RequestOriginPolicy {
+    pub fn from_settings(settings: &HttpSettings) -> Self {
+        let mut allowed_hosts: Vec<String> = settings
+            .allowed_hosts
+            .iter()
+            .map(|host| host.trim().to_ascii_lowercase())
+            .collect();
+        // Requests addressed to the configured interface are expected, unless it is a wildcard.
+        let bind_address = settings.bind_address.trim();
+        if !is_unspecified(bind_address) {
+            allowed_hosts.push(bind_address.to_ascii_lowercase());
+        }
+
+        Self {
+            allowed_origins: settings
+                .allowed_origins
+                .iter()
+                .map(|origin| normalize_origin(origin))
+                .collect(),
+            allowed_hosts,
+        }
+    }
+
+    fn allows_origin(&self, origin: &str) -> bool {
+        let origin = normalize_origin(origin);
+        if origin_host(&origin).is_some_and(is_loopback_host) {
+            return true;
+        }
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == ALLOW_ANY || *allowed == origin)
+    }
+
+    fn allows_host(&self, host: &str) -> bool {
+        let host = strip_port(host.trim()).to_ascii_lowercase();
+        if is_loopback_host(&host) {
+            return true;
+        }
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| allowed == ALLOW_ANY || *allowed == host)
+    }
+}
+
+pub(crate) fn validate_allow_lists(settings: &HttpSettings) -> Result<(), String> {
+    for origin in &settings.allowed_origins {
+        let origin = origin.trim();
+        if origin != ALLOW_ANY && !(origin.starts_with("http://") || origin.starts_with("https://"))
+        {
+            return Err(format!(
+                "Allowed origin '{origin}' must start with http:// or https://"
+            ));
+        }
+    }
+    if settings
+        .allowed_hosts
+        .iter()
+        .any(|host| host.trim().is_empty())
+    {
+        return Err("Allowed hosts cannot contain empty entries".into());
+    }
+    Ok(())
+}
+
+fn forbidden(message: &str) -> Response {
+    (StatusCode::FORBIDDEN, Json(json!({ "message": message }))).into_response()
+}
+
+/// Rejects requests whose `Origin` or `Host` header is not on the allow-list.
+///
+/// Requests without an `Origin` header (CLI tools, native agents) are accepted; browsers
+/// always send one on cross-origin requests, which is the case this guards against.
+pub(crate) async fn validate_origin_and_host(
+    State(policy): State<Arc<RequestOriginPolicy>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+
+    if let Some(host) = headers.get(header::HOST) {
+        let host = host.to_str().unwrap_or_default();
+        if !policy.allows_host(host) {
+            return forbidden("Host not allowed");
+        }
+    }
+
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        let origin = origin.to_str().unwrap_or_default();
+        if !policy.allows_origin(origin) {
+            return forbidden("Origin not allowed");
+        }
+    }
+
+    next.run(request).await
+}
+
+fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+/// Extracts the host from `scheme://host[:port]`; `None` for opaque origins such as `null`.
+fn origin_host(origin: &str) -> Option<&str> {
+    let (_, authority) = origin.split_once("://")?;
+    Some(strip_port(authority))
+}
+
+fn strip_port(authority: &str) -> &str {
+    if let Some(rest) = authority.strip_prefix('[') {
+        // Bracketed IPv6 literal, e.g. `[::1]:8080`.
+        return rest.split(']').next().unwrap_or_default();
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => authority,
+    }
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn is_unspecified(address: &str) -> bool {
+    address
+        .trim_matches(|c| c == '[' || c == ']')
+        .parse::<IpAddr>()
+        .is_ok_and(|ip| ip.is_unspecified())
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+
+use crate::history::NotificationSource;
+use crate::levels::NotificationLevel;
+use crate::tasks::format_duration;
+use crate::Notification;
+
+// Every probe is its own polling loop; keep the number bounded.
+const MAX_PROBES: usize = 32;
+const DEFAULT_TIMEOUT_SECS: u64 = 5 * 60;
+const MAX_TIMEOUT_SECS: u64 = 24 * 60 * 60;
+// Retries start quickly, since servers often come up within a second, then back off.
+const FIRST_RETRY: Duration = Duration::from_millis(250);
+const MAX_RETRY: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What to wait for: `host:port`, a bare port on this machine, or an http(s) URL.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ProbeRequest {
+    pub target: String,
+    #[serde(default)]
+    pub agent: String,
+    /// What is being waited for, such as "Dev server"; defaults to the target.
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    /// For URLs, the status that counts as healthy; by default anything below 500 does.
+    #[serde(default)]
+    pub status: Option<u16>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum ProbeTarget {
+    Tcp {
+        host: String,
+        port: u16,
+    },
+    Http {
+        url: reqwest::Url,
+        status: Option<u16>,
+    },
+}
+
+impl ProbeTarget {
+    fn parse(target: &str, status: Option<u16>) -> Result<Self, String> {
+        if target.starts_with("http://") || target.starts_with("https://") {
+            let url = reqwest::Url::parse(target)
+                .map_err(|err| format!("'{target}' is not a URL: {err}"))?;
+            if status.is_some_and(|status| !(100..=599).contains(&status)) {
+                return Err("'status' must be an HTTP status code".into());
+            }
+            return Ok(Self::Http { url, status });
+        }
+        if status.is_some() {
+            return Err("'status' only applies to URLs".into());
+        }
+        let (host, port) = match target.rsplit_once(':') {
+            Some((host, port)) => (host.trim_start_matches('[').trim_end_matches(']'), port),
+            None => ("127.0.0.1", target),
+        };
+        let port = port
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port != 0)
+            .ok_or_else(|| format!("'{target}' must be host:port, a port or an http(s) URL"))?;
+        if host.is_empty() {
+            return Err(format!("'{target}' is missing a host"));
+        }
+        Ok(Self::Tcp {
+            host: host.to_owned(),
+            port,
+        })
+    }
+
+    /// One attempt; the error says why the target is not ready yet.
+    pub async fn check(&self, client: &reqwest::Client) -> Result<String, String> {
+        match self {
+            Self::Tcp { host, port } => {
+                match tokio::time::timeout(
+                    CONNECT_TIMEOUT,
+                    TcpStream::connect((host.as_str(), *port)),
+                )
+                .await
+                {
+                    Ok(Ok(_)) => Ok("Accepting connections".into()),
+                    Ok(Err(err)) => Err(err.to_string()),
+                    Err(_) => Err("Connection timed out".into()),
+                }
+            }
+            Self::Http { url, status } => {
+                let response = client
+                    .get(url.clone())
+                    .send()
+                    .await
+                    .map_err(describe_error)?;
+                let code = response.status();
+                let healthy = match status {
+                    Some(status) => code.as_u16() == *status,
+                    None => !code.is_server_error(),
+                };
+                if healthy {
+                    Ok(format!("Responded with HTTP {}", code.as_u16()))
+                } else {
+                    Err(format!("HTTP {}", code.as_u16()))
+                }
+            }
+        }
+    }
+}
+
+/// The HTTP client a probe reuses for every attempt.
+pub(crate) fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(|err| format!("Failed to create an HTTP client: {err}"))
+}
+
+/// reqwest repeats the URL in its errors, which the notification already names.
+fn describe_error(err: reqwest::Error) -> String {
+    if err.is_timeout() {
+        "Request timed out".into()
+    } else if err.is_connect() {
+        "Could not connect".into()
+    } else {
+        err.without_url().to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Probe {
+    pub id: String,
+    pub target: String,
+    pub agent: String,
+    pub title: String,
+    /// Unix timestamp in milliseconds.
+    pub started_at: u64,
+    /// When the probe gives up, unix milliseconds.
+    pub deadline_at: u64,
+    pub attempts: u32,
+    /// Why the last attempt failed.
+    pub last_error: Option<String>,
+    #[serde(skip)]
+    pub source: NotificationSource,
+}
+
+impl Probe {
+    /// Checks a request and builds the probe for it; the agent must already be resolved.
+    pub fn new(
+        request: ProbeRequest,
+        source: NotificationSource,
+        now: u64,
+    ) -> Result<(Self, ProbeTarget), String> {
+        let target = request.target.trim();
+        let parsed = ProbeTarget::parse(target, request.status)?;
+        let agent = request.agent.trim();
+        if agent.is_empty() {
+            return Err("'agent' is required".into());
+        }
+        let timeout = request.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECS);
+        if timeout == 0 || timeout > MAX_TIMEOUT_SECS {
+            return Err(format!(
+                "'timeout_seconds' must be between 1 and {MAX_TIMEOUT_SECS}"
+            ));
+        }
+        let title = request
+            .title
+            .map(|title| title.trim().to_owned())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| target.to_owned());
+        let probe = Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            target: target.to_owned(),
+            agent: agent.to_owned(),
+            title,
+            started_at: now,
+            deadline_at: now + timeout * 1000,
+            attempts: 0,
+            last_error: None,
+            source,
+        };
+        Ok((probe, parsed))
+    }
+}
+
+/// How a probe ended.
+pub(crate) struct ProbeResult {
+    pub probe: Probe,
+    /// What the successful attempt saw, or `None` if the probe timed out.
+    pub ready: Option<String>,
+    pub finished_at: u64,
+}
+
+impl ProbeResult {
+    pub fn notification(&self) -> Notification {
+        let probe = &self.probe;
+        let waited = format_duration(self.finished_at.saturating_sub(probe.started_at));
+        let attempts = match probe.attempts {
+            1 => "1 attempt".to_owned(),
+            count => format!("{count} attempts"),
+        };
+        let (title, content, level) = match &self.ready {
+            Some(detail) => (
+                format!("{} is up", probe.title),
+                format!("{detail} after {waited} ({attempts}). {}", probe.target),
+                NotificationLevel::Success,
+            ),
+            None => (
+                format!("{} did not come up", probe.title),
+                format!(
+                    "Gave up after {waited} ({attempts}). {}: {}",
+                    probe.target,
+                    probe.last_error.as_deref().unwrap_or("not reachable")
+                ),
+                NotificationLevel::Error,
+            ),
+        };
+        Notification {
+            title,
+            content,
+            agent: probe.agent.clone(),
+            level,
+            silent: false,
+            source: probe.source,
+            url: None,
+            actions: Vec::new(),
+            reply_to: None,
+        }
+    }
+}
+
+/// What the polling loop should do after an attempt.
+pub(crate) enum Progress {
+    Retry(Duration),
+    Done(ProbeResult),
+    Cancelled,
+}
+
+/// Probes that are still waiting for their target.
+#[derive(Default)]
+pub(crate) struct ProbeRegistry {
+    probes: Mutex<HashMap<String, Probe>>,
+}
+
+impl ProbeRegistry {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Probe>> {
+        self.probes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn add(&self, probe: Probe) -> Result<(), String> {
+        let mut probes = self.lock();
+        if probes.len() >= MAX_PROBES {
+            return Err(format!("At most {MAX_PROBES} probes can run at once"));
+        }
+        probes.insert(probe.id.clone(), probe);
+        Ok(())
+    }
+
+    /// Running probes, oldest first.
+    pub fn list(&self) -> Vec<Probe> {
+        let mut probes: Vec<Probe> = self.lock().values().cloned().collect();
+        probes.sort_by_key(|probe| probe.started_at);
+        probes
+    }
+
+    /// Stops a probe without notifying; `owner` limits it to probes for that agent.
+    pub fn cancel(&self, id: &str, owner: Option<&str>) -> Option<Probe> {
+        let mut probes = self.lock();
+        if owner.is_some_and(|owner| probes.get(id).is_some_and(|probe| probe.agent != owner)) {
+            return None;
+        }
+        probes.remove(id)
+    }
+
+    /// Records an attempt and decides whether to try again, backing off each time but never
+    /// waiting past the deadline.
+    pub fn record(&self, id: &str, attempt: Result<String, String>, now: u64) -> Progress {
+        let mut probes = self.lock();
+        let Some(probe) = probes.get_mut(id) else {
+            return Progress::Cancelled;
+        };
+        probe.attempts += 1;
+        let ready = match attempt {
+            Ok(detail) => Some(detail),
+            Err(err) => {
+                probe.last_error = Some(err);
+                None
+            }
+        };
+        if ready.is_none() && now < probe.deadline_at {
+            let backoff = FIRST_RETRY
+                .saturating_mul(1 << probe.attempts.saturating_sub(1).min(8))
+                .min(MAX_RETRY);
+            let remaining = Duration::from_millis(probe.deadline_at - now);
+            return Progress::Retry(backoff.min(remaining));
+        }
+        let probe = probes.remove(id).expect("probe was just found");
+        Progress::Done(ProbeResult {
+            probe,
+            ready,
+            finished_at: now,
+        })
+    }
+}
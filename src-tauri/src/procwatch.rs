@@ -0,0 +1,382 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::NotificationSource;
+use crate::levels::NotificationLevel;
+use crate::tasks::format_duration;
+use crate::Notification;
+
+// Each watch is checked every second; keep the work bounded.
+const MAX_WATCHES: usize = 64;
+
+/// What to watch: a PID, or a process whose name matches.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ProcessWatchRequest {
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// Matched against the process name, then its command line.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub agent: String,
+    /// Title of the notification sent when it ends; defaults to "<name> finished".
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ProcessWatch {
+    pub id: String,
+    pub pid: u32,
+    pub name: String,
+    pub command: String,
+    pub agent: String,
+    pub title: String,
+    /// When the process started, unix milliseconds, if the platform says.
+    pub process_started_at: Option<u64>,
+    /// Unix timestamp in milliseconds.
+    pub watching_since: u64,
+    #[serde(skip)]
+    pub source: NotificationSource,
+}
+
+/// A watched process that is gone.
+pub(crate) struct Ended {
+    pub watch: ProcessWatch,
+    /// Only known when the process was caught before its parent collected it.
+    pub exit_code: Option<i32>,
+    pub ended_at: u64,
+}
+
+impl Ended {
+    pub fn notification(&self) -> Notification {
+        let watch = &self.watch;
+        let runtime = match watch.process_started_at {
+            Some(started) => format_duration(self.ended_at.saturating_sub(started)),
+            None => format!(
+                "at least {}",
+                format_duration(self.ended_at.saturating_sub(watch.watching_since))
+            ),
+        };
+        let (content, level) = match self.exit_code {
+            Some(0) => (
+                format!("Exited successfully after {runtime}."),
+                NotificationLevel::Success,
+            ),
+            Some(code) => (
+                format!("Exited with status {code} after {runtime}."),
+                NotificationLevel::Error,
+            ),
+            None => (
+                format!("Exited after {runtime}. Its exit status is only visible to its parent."),
+                NotificationLevel::Info,
+            ),
+        };
+        Notification {
+            title: watch.title.clone(),
+            content: format!("{content} PID {}: {}", watch.pid, watch.command),
+            agent: watch.agent.clone(),
+            level,
+            silent: false,
+            source: watch.source,
+            url: None,
+            actions: Vec::new(),
+            reply_to: None,
+        }
+    }
+}
+
+/// Processes being watched until they exit.
+#[derive(Default)]
+pub(crate) struct ProcessWatcher {
+    watches: Mutex<Vec<ProcessWatch>>,
+}
+
+impl ProcessWatcher {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<ProcessWatch>> {
+        self.watches.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Finds the process and starts watching it. Blocks briefly on the process table.
+    pub fn watch(
+        &self,
+        request: ProcessWatchRequest,
+        source: NotificationSource,
+        now: u64,
+    ) -> Result<ProcessWatch, String> {
+        if !platform::SUPPORTED {
+            return Err("Watching processes is not supported on this platform".into());
+        }
+        let agent = request.agent.trim();
+        if agent.is_empty() {
+            return Err("'agent' is required".into());
+        }
+        let pid = match (request.pid, request.name.as_deref().map(str::trim)) {
+            (Some(pid), None) => pid,
+            (None, Some(name)) if !name.is_empty() => find_by_name(name)?,
+            _ => return Err("Pass either 'pid' or a non-empty 'name'".into()),
+        };
+        if pid == std::process::id() {
+            return Err("The app cannot watch itself".into());
+        }
+        let info = platform::inspect(pid).ok_or_else(|| format!("No running process {pid}"))?;
+        let title = request
+            .title
+            .map(|title| title.trim().to_owned())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| format!("{} finished", info.name));
+
+        let watch = ProcessWatch {
+            id: uuid::Uuid::new_v4().to_string(),
+            pid,
+            name: info.name,
+            command: info.command,
+            agent: agent.to_owned(),
+            title,
+            process_started_at: info.started_at,
+            watching_since: now,
+            source,
+        };
+        let mut watches = self.lock();
+        if watches.len() >= MAX_WATCHES {
+            return Err(format!(
+                "At most {MAX_WATCHES} processes can be watched at once"
+            ));
+        }
+        watches.push(watch.clone());
+        Ok(watch)
+    }
+
+    pub fn list(&self) -> Vec<ProcessWatch> {
+        self.lock().clone()
+    }
+
+    /// Stops a watch; `owner` limits it to watches for that agent.
+    pub fn cancel(&self, id: &str, owner: Option<&str>) -> Option<ProcessWatch> {
+        let mut watches = self.lock();
+        let index = watches
+            .iter()
+            .position(|watch| watch.id == id && owner.is_none_or(|owner| watch.agent == owner))?;
+        Some(watches.remove(index))
+    }
+
+    /// Removes and returns every watched process that has exited. Blocks on the process table.
+    pub fn take_ended(&self, now: u64) -> Vec<Ended> {
+        let watches = self.list();
+        let mut ended = Vec::new();
+        for watch in watches {
+            let exit_code = match platform::inspect(watch.pid) {
+                // A different start time means the PID was recycled and ours is long gone.
+                Some(info)
+                    if info.started_at.is_some() && info.started_at != watch.process_started_at =>
+                {
+                    None
+                }
+                Some(info) if info.exit_code.is_none() => continue,
+                Some(info) => info.exit_code,
+                None => None,
+            };
+            ended.push(Ended {
+                watch,
+                exit_code,
+                ended_at: now,
+            });
+        }
+        if !ended.is_empty() {
+            self.lock()
+                .retain(|watch| !ended.iter().any(|ended| ended.watch.id == watch.id));
+        }
+        ended
+    }
+}
+
+/// Picks the single running process whose name, or failing that command line, matches.
+fn find_by_name(pattern: &str) -> Result<u32, String> {
+    let processes = platform::list();
+    let by_name: Vec<u32> = processes
+        .iter()
+        .filter(|(_, name, _)| name == pattern)
+        .map(|(pid, _, _)| *pid)
+        .collect();
+    let matches = if by_name.is_empty() {
+        processes
+            .iter()
+            .filter(|(_, _, command)| command.contains(pattern))
+            .map(|(pid, _, _)| *pid)
+            .collect()
+    } else {
+        by_name
+    };
+    let own = std::process::id();
+    let matches: Vec<u32> = matches.into_iter().filter(|pid| *pid != own).collect();
+    match matches.as_slice() {
+        [] => Err(format!("No running process matches '{pattern}'")),
+        [pid] => Ok(*pid),
+        many => Err(format!(
+            "{} processes match '{pattern}' (PIDs {}); pass a 'pid' instead",
+            many.len(),
+            many.iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+struct ProcessInfo {
+    name: String,
+    command: String,
+    /// Unix milliseconds; used to tell a recycled PID from the original process.
+    started_at: Option<u64>,
+    /// Set when the process has exited but its parent has not collected it yet.
+    exit_code: Option<i32>,
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+
+    use super::ProcessInfo;
+
+    pub(super) const SUPPORTED: bool = true;
+    // USER_HZ, which /proc reports times in, is 100 on every Linux architecture we ship.
+    const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+    pub(super) fn inspect(pid: u32) -> Option<ProcessInfo> {
+        let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        // The name is wrapped in parentheses and may itself contain spaces or parentheses.
+        let (head, rest) = stat.rsplit_once(')')?;
+        let name = head.split_once('(')?.1.to_owned();
+        // Fields after the name, starting with field 3 (state).
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        let state = *fields.first()?;
+        let started_at = fields
+            .get(19)
+            .and_then(|ticks| ticks.parse::<u64>().ok())
+            .and_then(|ticks| boot_time_ms().map(|boot| boot + ticks * 1000 / CLOCK_TICKS_PER_SEC));
+        // Field 52 (exit_code) holds the wait status once the process is a zombie.
+        let exit_code = (state == "Z")
+            .then(|| fields.get(49).and_then(|code| code.parse::<i32>().ok()))
+            .flatten()
+            .map(|status| match status & 0x7f {
+                // Exited normally; the code is in the high byte.
+                0 => (status >> 8) & 0xff,
+                // Killed by a signal; report it the way shells do.
+                signal => 128 + signal,
+            });
+        if state == "Z" && exit_code.is_none() {
+            return None;
+        }
+        let command = command_line(pid).unwrap_or_else(|| name.clone());
+        Some(ProcessInfo {
+            name,
+            command,
+            started_at,
+            exit_code,
+        })
+    }
+
+    pub(super) fn list() -> Vec<(u32, String, String)> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| {
+                let name = fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+                let name = name.trim_end().to_owned();
+                let command = command_line(pid).unwrap_or_else(|| name.clone());
+                Some((pid, name, command))
+            })
+            .collect()
+    }
+
+    fn command_line(pid: u32) -> Option<String> {
+        let raw = fs::read(format!("/proc/{pid}/cmdline")).ok()?;
+        let command = raw
+            .split(|byte| *byte == 0)
+            .filter(|part| !part.is_empty())
+            .map(String::from_utf8_lossy)
+            .collect::<Vec<_>>()
+            .join(" ");
+        (!command.is_empty()).then_some(command)
+    }
+
+    fn boot_time_ms() -> Option<u64> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        stat.lines()
+            .find_map(|line| line.strip_prefix("btime "))
+            .and_then(|secs| secs.trim().parse::<u64>().ok())
+            .map(|secs| secs * 1000)
+    }
+}
+
+// Elsewhere on Unix there is no /proc, so ask `ps`. It cannot see exit codes.
+#[cfg(all(unix, not(target_os = "linux")))]
+mod platform {
+    use std::process::Command;
+
+    use super::ProcessInfo;
+
+    pub(super) const SUPPORTED: bool = true;
+
+    fn ps(args: &[&str]) -> Option<String> {
+        let output = Command::new("ps").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    pub(super) fn inspect(pid: u32) -> Option<ProcessInfo> {
+        let pid = pid.to_string();
+        let name = ps(&["-o", "comm=", "-p", &pid])?.trim().to_owned();
+        if name.is_empty() {
+            return None;
+        }
+        let command = ps(&["-o", "command=", "-p", &pid])
+            .map(|command| command.trim().to_owned())
+            .filter(|command| !command.is_empty())
+            .unwrap_or_else(|| name.clone());
+        let name = name.rsplit('/').next().unwrap_or(&name).to_owned();
+        Some(ProcessInfo {
+            name,
+            command,
+            started_at: None,
+            exit_code: None,
+        })
+    }
+
+    pub(super) fn list() -> Vec<(u32, String, String)> {
+        let Some(output) = ps(&["-Ao", "pid=,command="]) else {
+            return Vec::new();
+        };
+        output
+            .lines()
+            .filter_map(|line| {
+                let (pid, command) = line.trim().split_once(' ')?;
+                let command = command.trim().to_owned();
+                let program = command.split_whitespace().next()?;
+                let name = program.rsplit('/').next()?.to_owned();
+                Some((pid.parse().ok()?, name, command))
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use super::ProcessInfo;
+
+    pub(super) const SUPPORTED: bool = false;
+
+    pub(super) fn inspect(_pid: u32) -> Option<ProcessInfo> {
+        None
+    }
+
+    pub(super) fn list() -> Vec<(u32, String, String)> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+pub(crate) const PROMPT_OPENED_EVENT: &str = "prompt-opened";
+pub(crate) const PROMPT_CLOSED_EVENT: &str = "prompt-closed";
+pub(crate) const MCP_SETTINGS_KEY: &str = "mcpTools";
+
+/// User-facing switches for MCP tools that change what `tools/list` returns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct McpToolSettings {
+    /// Lets agents block on `ask_user`; off means they cannot interrupt with questions.
+    #[serde(default = "default_true")]
+    pub ask_user_enabled: bool,
+}
+
+impl Default for McpToolSettings {
+    fn default() -> Self {
+        Self {
+            ask_user_enabled: true,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A question from an agent waiting for the user, as shown in the settings window.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PendingPrompt {
+    pub id: String,
+    pub agent: String,
+    pub question: String,
+    pub choices: Vec<String>,
+    pub allow_text: bool,
+    /// Unix timestamp in milliseconds.
+    pub created_at: u64,
+    /// Unix timestamp in milliseconds.
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PromptAnswer {
+    pub choice: Option<String>,
+    pub text: Option<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct PromptRegistry {
+    pending: Mutex<HashMap<String, Entry>>,
+}
+
+type Entry = (PendingPrompt, oneshot::Sender<PromptAnswer>);
+
+/// Keeps a prompt on screen for as long as the agent is waiting on it.
+///
+/// Dropping the guard, whether after an answer, a timeout or the client hanging up, takes the
+/// prompt down again.
+pub(crate) struct OpenPrompt {
+    registry: Arc<PromptRegistry>,
+    app: AppHandle,
+    id: String,
+}
+
+impl Drop for OpenPrompt {
+    fn drop(&mut self) {
+        self.registry.close(&self.id);
+        if let Err(err) = self.app.emit(PROMPT_CLOSED_EVENT, &self.id) {
+            eprintln!("Failed to emit {PROMPT_CLOSED_EVENT}: {err}");
+        }
+    }
+}
+
+impl PromptRegistry {
+    /// Registers a prompt and announces it to the settings window.
+    pub fn open(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        prompt: PendingPrompt,
+    ) -> (OpenPrompt, oneshot::Receiver<PromptAnswer>) {
+        let (sender, receiver) = oneshot::channel();
+        let id = prompt.id.clone();
+        if let Err(err) = app.emit(PROMPT_OPENED_EVENT, &prompt) {
+            eprintln!("Failed to emit {PROMPT_OPENED_EVENT}: {err}");
+        }
+        self.lock().insert(id.clone(), (prompt, sender));
+
+        let guard = OpenPrompt {
+            registry: self.clone(),
+            app: app.clone(),
+            id,
+        };
+        (guard, receiver)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Resolves a prompt with the user's answer, checking it against what the agent allowed.
+    pub fn answer(&self, id: &str, answer: PromptAnswer) -> Result<(), String> {
+        let mut pending = self.lock();
+        let Some((prompt, _)) = pending.get(id) else {
+            return Err("This question has already been answered or has expired".into());
+        };
+
+        let choice = answer.choice.as_deref().map(str::trim);
+        let text = answer.text.as_deref().map(str::trim);
+        match (choice, text) {
+            (Some(choice), _) if !prompt.choices.iter().any(|c| c == choice) => {
+                return Err(format!("'{choice}' is not one of the offered choices"));
+            }
+            (None, Some(text)) if !text.is_empty() && !prompt.allow_text => {
+                return Err("This question only accepts one of the offered choices".into());
+            }
+            (None, None) | (None, Some("")) => {
+                return Err("Pick a choice or type an answer".into());
+            }
+            _ => {}
+        }
+        // Text sent along with a choice is dropped unless the question asked for it.
+        let text = text.filter(|text| !text.is_empty() && prompt.allow_text);
+
+        let (_, sender) = pending.remove(id).expect("prompt checked above");
+        // The agent may have given up in the meantime; nothing left to deliver to.
+        let _ = sender.send(PromptAnswer {
+            choice: choice.map(str::to_owned),
+            text: text.map(str::to_owned),
+        });
+        Ok(())
+    }
+
+    fn close(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// Open prompts, oldest first.
+    pub fn list(&self) -> Vec<PendingPrompt> {
+        let mut prompts: Vec<PendingPrompt> = self
+            .lock()
+            .values()
+            .map(|(prompt, _)| prompt.clone())
+            .collect();
+        prompts.sort_by_key(|prompt| prompt.created_at);
+        prompts
+    }
+}
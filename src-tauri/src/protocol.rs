@@ -0,0 +1,34 @@
+pub(crate) const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// MCP revisions this server can speak, newest first. Older entries keep agent frameworks that
+/// still target earlier Streamable HTTP revisions working.
+pub(crate) const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-11-25", "2025-06-18", "2025-03-26"];
+
+// First revision with `structuredContent`, tool `outputSchema` and `title` fields.
+const STRUCTURED_OUTPUT_VERSION: &str = "2025-06-18";
+
+pub(crate) fn latest_protocol_version() -> &'static str {
+    SUPPORTED_PROTOCOL_VERSIONS[0]
+}
+
+pub(crate) fn is_supported(version: &str) -> bool {
+    SUPPORTED_PROTOCOL_VERSIONS.contains(&version)
+}
+
+/// Echo the client's requested revision when we support it, otherwise offer our latest and let
+/// the client decide whether to disconnect.
+pub(crate) fn negotiate(requested: Option<&str>) -> &'static str {
+    requested
+        .and_then(|requested| {
+            SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .find(|supported| **supported == requested)
+        })
+        .copied()
+        .unwrap_or_else(latest_protocol_version)
+}
+
+/// Revisions are ISO dates, so lexical order matches release order.
+pub(crate) fn supports_structured_output(version: &str) -> bool {
+    version >= STRUCTURED_OUTPUT_VERSION
+}
@@ -0,0 +1,317 @@
+use std::sync::Mutex;
+
+use chrono::{Datelike, Local, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::Notification;
+
+pub(crate) const QUIET_SETTINGS_KEY: &str = "quietHours";
+// Held in memory until quiet time ends; past this the oldest are dropped from the digest
+// (they are still in the history).
+const MAX_QUEUED_NOTIFICATIONS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Day {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Day {
+    /// Today and yesterday, from today's number of days since Monday.
+    fn today_and_yesterday(days_from_monday: u32) -> (Self, Self) {
+        (
+            Self::from_index(days_from_monday),
+            Self::from_index(days_from_monday + 6),
+        )
+    }
+
+    fn from_index(index: u32) -> Self {
+        match index % 7 {
+            0 => Self::Mon,
+            1 => Self::Tue,
+            2 => Self::Wed,
+            3 => Self::Thu,
+            4 => Self::Fri,
+            5 => Self::Sat,
+            _ => Self::Sun,
+        }
+    }
+}
+
+/// A recurring quiet period in local time. `end` before `start` runs past midnight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct QuietSchedule {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Days the period starts on; every day when empty.
+    #[serde(default)]
+    pub days: Vec<Day>,
+    /// `HH:MM`, 24-hour.
+    pub start: String,
+    /// `HH:MM`, 24-hour.
+    pub end: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl QuietSchedule {
+    fn starts_on(&self, day: Day) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    /// Whether `minute` (minutes since local midnight) on `today` falls inside this period.
+    fn covers(&self, today: Day, yesterday: Day, minute: u32) -> bool {
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        if !self.enabled {
+            return false;
+        }
+        if start < end {
+            self.starts_on(today) && (start..end).contains(&minute)
+        } else {
+            // Overnight: the evening part belongs to today, the early hours to yesterday.
+            (self.starts_on(today) && minute >= start)
+                || (self.starts_on(yesterday) && minute < end)
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct QuietSettings {
+    #[serde(default)]
+    pub schedules: Vec<QuietSchedule>,
+}
+
+impl QuietSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        for schedule in &self.schedules {
+            let start = parse_time(&schedule.start)
+                .ok_or_else(|| format!("'{}' is not a valid HH:MM time", schedule.start))?;
+            let end = parse_time(&schedule.end)
+                .ok_or_else(|| format!("'{}' is not a valid HH:MM time", schedule.end))?;
+            if start == end {
+                return Err("Quiet hours need different start and end times".into());
+            }
+        }
+        Ok(())
+    }
+
+    fn covers_now(&self) -> bool {
+        let now = Local::now();
+        let (today, yesterday) = Day::today_and_yesterday(now.weekday().num_days_from_monday());
+        let minute = now.hour() * 60 + now.minute();
+        self.schedules
+            .iter()
+            .any(|schedule| schedule.covers(today, yesterday, minute))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct QuietStatus {
+    pub active: bool,
+    /// Unix timestamp in milliseconds when a manual pause ends.
+    pub paused_until: Option<u64>,
+    pub in_schedule: bool,
+    pub queued: usize,
+}
+
+struct QuietState {
+    settings: QuietSettings,
+    paused_until: Option<u64>,
+    queue: Vec<Notification>,
+}
+
+/// Do-Not-Disturb: quiet-hour schedules plus manual pauses, and the notifications held back
+/// while either is in effect.
+pub(crate) struct QuietHours {
+    state: Mutex<QuietState>,
+}
+
+impl QuietHours {
+    pub fn new(settings: QuietSettings) -> Self {
+        Self {
+            state: Mutex::new(QuietState {
+                settings,
+                paused_until: None,
+                queue: Vec::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QuietState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn settings(&self) -> QuietSettings {
+        self.lock().settings.clone()
+    }
+
+    pub fn set_settings(&self, settings: QuietSettings) {
+        self.lock().settings = settings;
+    }
+
+    pub fn pause(&self, until: u64) {
+        self.lock().paused_until = Some(until);
+    }
+
+    pub fn resume(&self) {
+        self.lock().paused_until = None;
+    }
+
+    pub fn status(&self) -> QuietStatus {
+        let state = self.lock();
+        let paused_until = state
+            .paused_until
+            .filter(|until| *until > crate::unix_millis_now());
+        let in_schedule = state.settings.covers_now();
+        QuietStatus {
+            active: paused_until.is_some() || in_schedule,
+            paused_until,
+            in_schedule,
+            queued: state.queue.len(),
+        }
+    }
+
+    /// Holds the notification back if quiet time is in effect. Returns whether it was queued.
+    pub fn defer(&self, notification: &Notification) -> bool {
+        let mut state = self.lock();
+        if !state.is_active() {
+            return false;
+        }
+        state.queue.push(notification.clone());
+        if state.queue.len() > MAX_QUEUED_NOTIFICATIONS {
+            state.queue.remove(0);
+        }
+        true
+    }
+
+    /// Hands back everything queued once quiet time is over; empty while it still applies.
+    pub fn take_digest(&self) -> Vec<Notification> {
+        let mut state = self.lock();
+        if state.is_active() {
+            return Vec::new();
+        }
+        state.paused_until = None;
+        std::mem::take(&mut state.queue)
+    }
+}
+
+impl QuietState {
+    fn is_active(&self) -> bool {
+        self.paused_until
+            .is_some_and(|until| until > crate::unix_millis_now())
+            || self.settings.covers_now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(start: &str, end: &str, days: &[Day]) -> QuietSchedule {
+        QuietSchedule {
+            enabled: true,
+            days: days.to_vec(),
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    fn at(hours: u32, minutes: u32) -> u32 {
+        hours * 60 + minutes
+    }
+
+    #[test]
+    fn parses_times_within_a_day() {
+        assert_eq!(parse_time("00:00"), Some(0));
+        assert_eq!(parse_time(" 07:05 "), Some(at(7, 5)));
+        assert_eq!(parse_time("7:5"), Some(at(7, 5)));
+        assert_eq!(parse_time("23:59"), Some(at(23, 59)));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("12:60"), None);
+        assert_eq!(parse_time("-1:00"), None);
+        assert_eq!(parse_time("noon"), None);
+        assert_eq!(parse_time(""), None);
+    }
+
+    #[test]
+    fn daytime_period_includes_start_and_excludes_end() {
+        let work = schedule("09:00", "17:00", &[]);
+        assert!(!work.covers(Day::Mon, Day::Sun, at(8, 59)));
+        assert!(work.covers(Day::Mon, Day::Sun, at(9, 0)));
+        assert!(work.covers(Day::Mon, Day::Sun, at(16, 59)));
+        assert!(!work.covers(Day::Mon, Day::Sun, at(17, 0)));
+    }
+
+    #[test]
+    fn overnight_period_belongs_to_the_day_it_starts() {
+        let friday_night = schedule("22:00", "07:00", &[Day::Fri]);
+        assert!(!friday_night.covers(Day::Fri, Day::Thu, at(21, 59)));
+        assert!(friday_night.covers(Day::Fri, Day::Thu, at(22, 0)));
+        assert!(friday_night.covers(Day::Fri, Day::Thu, at(23, 59)));
+        assert!(friday_night.covers(Day::Sat, Day::Fri, at(0, 0)));
+        assert!(friday_night.covers(Day::Sat, Day::Fri, at(6, 59)));
+        assert!(!friday_night.covers(Day::Sat, Day::Fri, at(7, 0)));
+        // Thursday night is not covered, so Friday's early hours are not either.
+        assert!(!friday_night.covers(Day::Fri, Day::Thu, at(6, 0)));
+        assert!(!friday_night.covers(Day::Sat, Day::Fri, at(22, 0)));
+    }
+
+    #[test]
+    fn overnight_period_can_start_at_midnight_boundaries() {
+        let late = schedule("23:59", "00:00", &[]);
+        assert!(late.covers(Day::Mon, Day::Sun, at(23, 59)));
+        assert!(!late.covers(Day::Tue, Day::Mon, at(0, 0)));
+
+        let from_midnight = schedule("00:00", "06:00", &[Day::Sun]);
+        assert!(from_midnight.covers(Day::Sun, Day::Sat, at(0, 0)));
+        assert!(!from_midnight.covers(Day::Mon, Day::Sun, at(0, 0)));
+    }
+
+    #[test]
+    fn disabled_or_malformed_schedules_never_apply() {
+        let mut night = schedule("22:00", "07:00", &[]);
+        night.enabled = false;
+        assert!(!night.covers(Day::Mon, Day::Sun, at(23, 0)));
+        assert!(!schedule("22:00", "7am", &[]).covers(Day::Mon, Day::Sun, at(23, 0)));
+    }
+
+    #[test]
+    fn yesterday_wraps_from_monday_to_sunday() {
+        assert_eq!(Day::today_and_yesterday(0), (Day::Mon, Day::Sun));
+        assert_eq!(Day::today_and_yesterday(2), (Day::Wed, Day::Tue));
+        assert_eq!(Day::today_and_yesterday(6), (Day::Sun, Day::Sat));
+    }
+
+    #[test]
+    fn validate_rejects_bad_and_empty_periods() {
+        let settings = |start: &str, end: &str| QuietSettings {
+            schedules: vec![schedule(start, end, &[])],
+        };
+        assert!(settings("22:00", "07:00").validate().is_ok());
+        assert_eq!(
+            settings("25:00", "07:00").validate().unwrap_err(),
+            "'25:00' is not a valid HH:MM time"
+        );
+        assert_eq!(
+            settings("08:00", "08:00").validate().unwrap_err(),
+            "Quiet hours need different start and end times"
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+pub(crate) const RATE_LIMIT_SETTINGS_KEY: &str = "rateLimits";
+// Idle buckets are full again and can be forgotten; only bother once there are this many.
+const PRUNE_THRESHOLD: usize = 256;
+
+/// A token bucket: up to `burst` notifications at once, refilled at `per_minute`.
+/// A `per_minute` of 0 means no limit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct RateLimit {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    const fn new(per_minute: u32, burst: u32) -> Self {
+        Self { per_minute, burst }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.per_minute == 0
+    }
+
+    fn refill_per_second(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct RateLimitSettings {
+    /// Each agent label.
+    pub per_agent: RateLimit,
+    /// Each client IP address.
+    pub per_client: RateLimit,
+    /// Everything together.
+    pub global: RateLimit,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            per_agent: RateLimit::new(30, 10),
+            per_client: RateLimit::new(60, 20),
+            global: RateLimit::new(120, 30),
+        }
+    }
+}
+
+impl RateLimitSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, limit) in [
+            ("per-agent", self.per_agent),
+            ("per-client", self.per_client),
+            ("global", self.global),
+        ] {
+            if !limit.is_unlimited() && limit.burst == 0 {
+                return Err(format!("The {name} limit needs a burst of at least 1"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which limit a rejected notification ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum LimitScope {
+    Agent,
+    Client,
+    Global,
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimited {
+    pub scope: LimitScope,
+    pub retry_after: Duration,
+}
+
+impl RateLimited {
+    /// Whole seconds to wait, rounded up so a retry right on time succeeds.
+    pub fn retry_after_secs(&self) -> u64 {
+        self.retry_after.as_secs() + u64::from(self.retry_after.subsec_nanos() > 0)
+    }
+
+    pub fn message(&self) -> String {
+        let limit = match self.scope {
+            LimitScope::Agent => "this agent",
+            LimitScope::Client => "this client",
+            LimitScope::Global => "all agents",
+        };
+        format!(
+            "Rate limit for {limit} exceeded; retry in {}s",
+            self.retry_after_secs()
+        )
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(limit.burst),
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * limit.refill_per_second()).min(f64::from(limit.burst));
+        self.updated = now;
+    }
+
+    /// How long until a token is available; zero if one is available now.
+    fn wait(&self, limit: RateLimit) -> Duration {
+        if self.tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((1.0 - self.tokens) / limit.refill_per_second())
+    }
+
+    fn is_full(&self, limit: RateLimit) -> bool {
+        self.tokens >= f64::from(limit.burst)
+    }
+}
+
+struct LimiterState {
+    settings: RateLimitSettings,
+    agents: HashMap<String, Bucket>,
+    clients: HashMap<IpAddr, Bucket>,
+    global: Bucket,
+}
+
+/// Token-bucket limits on notifications per agent, per client address and overall.
+pub(crate) struct RateLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        let global = Bucket::full(settings.global, Instant::now());
+        Self {
+            state: Mutex::new(LimiterState {
+                settings,
+                agents: HashMap::new(),
+                clients: HashMap::new(),
+                global,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn settings(&self) -> RateLimitSettings {
+        self.lock().settings.clone()
+    }
+
+    /// Applies new limits, starting every bucket full.
+    pub fn set_settings(&self, settings: RateLimitSettings) {
+        let mut state = self.lock();
+        state.global = Bucket::full(settings.global, Instant::now());
+        state.agents.clear();
+        state.clients.clear();
+        state.settings = settings;
+    }
+
+    /// Takes one token from each bucket the notification counts against, or none of them if
+    /// any is empty.
+    pub fn check(&self, agent: &str, client: IpAddr) -> Result<(), RateLimited> {
+        self.check_at(agent, client, Instant::now())
+    }
+
+    fn check_at(&self, agent: &str, client: IpAddr, now: Instant) -> Result<(), RateLimited> {
+        let mut guard = self.lock();
+        let state = &mut *guard;
+        let settings = state.settings.clone();
+
+        if state.agents.len() > PRUNE_THRESHOLD || state.clients.len() > PRUNE_THRESHOLD {
+            state.agents.retain(|_, bucket| {
+                bucket.refill(settings.per_agent, now);
+                !bucket.is_full(settings.per_agent)
+            });
+            state.clients.retain(|_, bucket| {
+                bucket.refill(settings.per_client, now);
+                !bucket.is_full(settings.per_client)
+            });
+        }
+
+        let agent_bucket = state
+            .agents
+            .entry(agent.to_owned())
+            .or_insert_with(|| Bucket::full(settings.per_agent, now));
+        let client_bucket = state
+            .clients
+            .entry(client)
+            .or_insert_with(|| Bucket::full(settings.per_client, now));
+        let mut buckets = [
+            (LimitScope::Agent, settings.per_agent, agent_bucket),
+            (LimitScope::Client, settings.per_client, client_bucket),
+            (LimitScope::Global, settings.global, &mut state.global),
+        ];
+
+        let mut limited: Option<RateLimited> = None;
+        for (scope, limit, bucket) in buckets.iter_mut() {
+            if limit.is_unlimited() {
+                continue;
+            }
+            bucket.refill(*limit, now);
+            let wait = bucket.wait(*limit);
+            // Report the limit that keeps the caller waiting longest.
+            if !wait.is_zero() && limited.as_ref().is_none_or(|l| wait > l.retry_after) {
+                limited = Some(RateLimited {
+                    scope: *scope,
+                    retry_after: wait,
+                });
+            }
+        }
+        if let Some(limited) = limited {
+            return Err(limited);
+        }
+
+        for (_, limit, bucket) in buckets.iter_mut() {
+            if !limit.is_unlimited() {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    const UNLIMITED: RateLimit = RateLimit::new(0, 0);
+
+    fn limiter(per_agent: RateLimit, per_client: RateLimit, global: RateLimit) -> RateLimiter {
+        RateLimiter::new(RateLimitSettings {
+            per_agent,
+            per_client,
+            global,
+        })
+    }
+
+    fn limited(wait: Duration) -> RateLimited {
+        RateLimited {
+            scope: LimitScope::Agent,
+            retry_after: wait,
+        }
+    }
+
+    #[test]
+    fn allows_a_full_burst_then_waits_for_one_token() {
+        let limiter = limiter(RateLimit::new(30, 10), UNLIMITED, UNLIMITED);
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert!(limiter.check_at("codex", CLIENT, now).is_ok());
+        }
+
+        let err = limiter.check_at("codex", CLIENT, now).unwrap_err();
+        assert_eq!(err.scope, LimitScope::Agent);
+        // 30 per minute refills one token every two seconds.
+        assert_eq!(err.retry_after, Duration::from_secs(2));
+        assert_eq!(err.retry_after_secs(), 2);
+    }
+
+    #[test]
+    fn refills_with_time_but_never_past_the_burst() {
+        let limiter = limiter(RateLimit::new(60, 2), UNLIMITED, UNLIMITED);
+        let now = Instant::now();
+        assert!(limiter.check_at("codex", CLIENT, now).is_ok());
+        assert!(limiter.check_at("codex", CLIENT, now).is_ok());
+        assert!(limiter.check_at("codex", CLIENT, now).is_err());
+
+        let half = now + Duration::from_millis(500);
+        let err = limiter.check_at("codex", CLIENT, half).unwrap_err();
+        assert_eq!(err.retry_after_secs(), 1);
+        assert!(limiter
+            .check_at("codex", CLIENT, now + Duration::from_secs(1))
+            .is_ok());
+
+        // An hour idle still only refills up to the burst.
+        let later = now + Duration::from_secs(3600);
+        assert!(limiter.check_at("codex", CLIENT, later).is_ok());
+        assert!(limiter.check_at("codex", CLIENT, later).is_ok());
+        assert!(limiter.check_at("codex", CLIENT, later).is_err());
+    }
+
+    #[test]
+    fn retry_after_rounds_partial_seconds_up() {
+        assert_eq!(limited(Duration::ZERO).retry_after_secs(), 0);
+        assert_eq!(limited(Duration::from_nanos(1)).retry_after_secs(), 1);
+        assert_eq!(limited(Duration::from_millis(1200)).retry_after_secs(), 2);
+        assert_eq!(limited(Duration::from_secs(3)).retry_after_secs(), 3);
+        assert_eq!(
+            limited(Duration::from_millis(1500)).message(),
+            "Rate limit for this agent exceeded; retry in 2s"
+        );
+    }
+
+    #[test]
+    fn agents_have_separate_buckets() {
+        let limiter = limiter(RateLimit::new(60, 1), UNLIMITED, UNLIMITED);
+        let now = Instant::now();
+        assert!(limiter.check_at("codex", CLIENT, now).is_ok());
+        assert!(limiter.check_at("codex", CLIENT, now).is_err());
+        assert!(limiter.check_at("claude", CLIENT, now).is_ok());
+    }
+
+    #[test]
+    fn reports_the_limit_that_waits_longest_and_takes_nothing_when_limited() {
+        // The agent bucket refills every six seconds, the global one every second.
+        let limiter = limiter(RateLimit::new(10, 1), UNLIMITED, RateLimit::new(60, 1));
+        let now = Instant::now();
+        assert!(limiter.check_at("codex", CLIENT, now).is_ok());
+
+        let err = limiter.check_at("codex", CLIENT, now).unwrap_err();
+        assert_eq!(err.scope, LimitScope::Agent);
+        assert_eq!(err.retry_after_secs(), 6);
+
+        let err = limiter.check_at("claude", CLIENT, now).unwrap_err();
+        assert_eq!(err.scope, LimitScope::Global);
+        assert_eq!(err.retry_after_secs(), 1);
+        // Had the rejected call taken claude's only token, this would wait six seconds.
+        assert!(limiter
+            .check_at("claude", CLIENT, now + Duration::from_secs(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn zero_per_minute_never_limits() {
+        let limiter = limiter(UNLIMITED, UNLIMITED, UNLIMITED);
+        let now = Instant::now();
+        for _ in 0..1000 {
+            assert!(limiter.check_at("codex", CLIENT, now).is_ok());
+        }
+    }
+
+    #[test]
+    fn new_settings_start_every_bucket_full() {
+        let limiter = limiter(RateLimit::new(60, 1), UNLIMITED, UNLIMITED);
+        assert!(limiter.check("codex", CLIENT).is_ok());
+        assert!(limiter.check("codex", CLIENT).is_err());
+
+        limiter.set_settings(RateLimitSettings {
+            per_agent: RateLimit::new(60, 2),
+            per_client: UNLIMITED,
+            global: UNLIMITED,
+        });
+        assert!(limiter.check("codex", CLIENT).is_ok());
+        assert!(limiter.check("codex", CLIENT).is_ok());
+    }
+
+    #[test]
+    fn validate_needs_a_burst_unless_unlimited() {
+        assert!(RateLimitSettings::default().validate().is_ok());
+
+        let mut settings = RateLimitSettings {
+            per_agent: UNLIMITED,
+            per_client: UNLIMITED,
+            global: UNLIMITED,
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.per_client = RateLimit::new(10, 0);
+        assert_eq!(
+            settings.validate().unwrap_err(),
+            "The per-client limit needs a burst of at least 1"
+        );
+    }
+}
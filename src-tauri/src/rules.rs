@@ -0,0 +1,236 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::{decode_sound, DecodedSound},
+    history::NotificationSource,
+    levels::NotificationLevel,
+    sinks::Sink,
+    Notification,
+};
+
+pub(crate) const RULES_SETTINGS_KEY: &str = "notificationRules";
+
+/// What a notification has to look like for a rule to apply. Empty fields match anything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct RuleConditions {
+    /// Exact agent label.
+    pub agent: Option<String>,
+    /// Regular expression searched for in the title.
+    pub title: Option<String>,
+    /// Regular expression searched for in the content.
+    pub content: Option<String>,
+    pub levels: Vec<NotificationLevel>,
+    pub sources: Vec<NotificationSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum RuleAction {
+    /// Drop the notification: no toast and no sound. It is still recorded in the history.
+    Mute,
+    /// Play this sound (bundled name or file path) instead of the usual one.
+    SetSound { sound: String },
+    /// Replace the title. `{title}`, `{agent}` and `{level}` are filled in.
+    RewriteTitle { title: String },
+    /// Also send the notification to a sink.
+    Forward { sink: Sink },
+    /// Raise the level, never lowering it.
+    Escalate { level: NotificationLevel },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Rule {
+    /// Assigned on save when left empty.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub conditions: RuleConditions,
+    pub actions: Vec<RuleAction>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct RuleSettings {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// The rule that applied to a notification, reported back to the caller.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct MatchedRule {
+    pub id: String,
+    pub name: String,
+}
+
+/// What the matching rule asks dispatch to do beyond the rewritten notification itself.
+#[derive(Default)]
+pub(crate) struct RuleEffect {
+    pub rule: Option<MatchedRule>,
+    pub muted: bool,
+    pub sound: Option<DecodedSound>,
+    pub sinks: Vec<Sink>,
+}
+
+struct CompiledRule {
+    title: Option<Regex>,
+    content: Option<Regex>,
+    sound: Option<DecodedSound>,
+}
+
+/// The saved rules with their patterns compiled and sounds decoded, ready to run on every
+/// notification. Rules are tried top to bottom and the first match wins.
+pub(crate) struct RuleSet {
+    settings: RuleSettings,
+    compiled: Vec<Option<CompiledRule>>,
+}
+
+impl RuleSet {
+    /// Checks every rule, failing on the first bad pattern, sound or sink. Decodes audio, so
+    /// call it off the async workers.
+    pub fn compile(settings: RuleSettings) -> Result<Self, String> {
+        let compiled = settings
+            .rules
+            .iter()
+            .map(|rule| compile_rule(rule).map(Some))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { settings, compiled })
+    }
+
+    /// Like `compile`, but rules that no longer compile (say, a sound file that was deleted)
+    /// are logged and skipped instead of failing the whole set.
+    pub fn load(settings: RuleSettings) -> Self {
+        let compiled = settings
+            .rules
+            .iter()
+            .map(|rule| match compile_rule(rule) {
+                Ok(compiled) => Some(compiled),
+                Err(err) => {
+                    eprintln!("Skipping notification rule '{}': {err}", rule.name);
+                    None
+                }
+            })
+            .collect();
+        Self { settings, compiled }
+    }
+
+    pub fn settings(&self) -> &RuleSettings {
+        &self.settings
+    }
+
+    /// Applies the first matching rule, rewriting `notification` in place.
+    pub fn apply(&self, notification: &mut Notification) -> RuleEffect {
+        let Some((rule, compiled)) = self
+            .settings
+            .rules
+            .iter()
+            .zip(&self.compiled)
+            .filter(|(rule, _)| rule.enabled)
+            .find_map(|(rule, compiled)| {
+                let compiled = compiled.as_ref()?;
+                matches(rule, compiled, notification).then_some((rule, compiled))
+            })
+        else {
+            return RuleEffect::default();
+        };
+
+        let mut effect = RuleEffect {
+            rule: Some(MatchedRule {
+                id: rule.id.clone(),
+                name: rule.name.clone(),
+            }),
+            sound: compiled.sound.clone(),
+            ..RuleEffect::default()
+        };
+        for action in &rule.actions {
+            match action {
+                RuleAction::Mute => effect.muted = true,
+                RuleAction::SetSound { .. } => {}
+                RuleAction::RewriteTitle { title } => {
+                    notification.title = title
+                        .replace("{title}", &notification.title)
+                        .replace("{agent}", &notification.agent)
+                        .replace("{level}", notification.level.as_str());
+                }
+                RuleAction::Forward { sink } => effect.sinks.push(sink.clone()),
+                RuleAction::Escalate { level } => {
+                    notification.level = notification.level.max(*level);
+                }
+            }
+        }
+        effect
+    }
+}
+
+fn compile_rule(rule: &Rule) -> Result<CompiledRule, String> {
+    if rule.name.trim().is_empty() {
+        return Err("Every rule needs a name".into());
+    }
+    if rule.actions.is_empty() {
+        return Err(format!("Rule '{}' has no actions", rule.name));
+    }
+    let pattern = |field: &Option<String>| {
+        field
+            .as_deref()
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|err| format!("Rule '{}' has an invalid pattern: {err}", rule.name))
+            })
+            .transpose()
+    };
+    let title = pattern(&rule.conditions.title)?;
+    let content = pattern(&rule.conditions.content)?;
+
+    let mut sound = None;
+    for action in &rule.actions {
+        match action {
+            RuleAction::SetSound { sound: name } => {
+                sound =
+                    Some(decode_sound(name).map_err(|err| format!("Rule '{}': {err}", rule.name))?);
+            }
+            RuleAction::Forward { sink } => sink
+                .validate()
+                .map_err(|err| format!("Rule '{}': {err}", rule.name))?,
+            RuleAction::RewriteTitle { title } if title.trim().is_empty() => {
+                return Err(format!(
+                    "Rule '{}' rewrites the title to nothing",
+                    rule.name
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(CompiledRule {
+        title,
+        content,
+        sound,
+    })
+}
+
+fn matches(rule: &Rule, compiled: &CompiledRule, notification: &Notification) -> bool {
+    let conditions = &rule.conditions;
+    conditions
+        .agent
+        .as_deref()
+        .filter(|agent| !agent.is_empty())
+        .is_none_or(|agent| agent == notification.agent)
+        && (conditions.levels.is_empty() || conditions.levels.contains(&notification.level))
+        && (conditions.sources.is_empty() || conditions.sources.contains(&notification.source))
+        && compiled
+            .title
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&notification.title))
+        && compiled
+            .content
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&notification.content))
+}
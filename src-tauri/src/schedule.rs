@@ -0,0 +1,199 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::actions::NotificationAction;
+use crate::history::NotificationSource;
+use crate::levels::NotificationLevel;
+use crate::Notification;
+
+pub(crate) const SCHEDULED_NOTIFICATIONS_KEY: &str = "scheduledNotifications";
+const MAX_SCHEDULED: usize = 500;
+const MAX_DELAY_SECS: u64 = 365 * 24 * 60 * 60;
+// Anything later than this was missed while the app was closed or the machine slept.
+const LATE_AFTER_MS: u64 = 60 * 1000;
+
+/// When to deliver a notification, from `deliver_at` (RFC 3339) or `delay_seconds`. `None`
+/// means right away.
+pub(crate) fn delivery_time(
+    deliver_at: Option<&str>,
+    delay_seconds: Option<u64>,
+    now: u64,
+) -> Result<Option<u64>, String> {
+    match (deliver_at.map(str::trim), delay_seconds) {
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) => Err("Pass either 'deliver_at' or 'delay_seconds', not both".into()),
+        (None, Some(delay)) => {
+            if delay == 0 || delay > MAX_DELAY_SECS {
+                return Err(format!(
+                    "'delay_seconds' must be between 1 and {MAX_DELAY_SECS}"
+                ));
+            }
+            Ok(Some(now + delay * 1000))
+        }
+        (Some(at), None) => {
+            let at = DateTime::parse_from_rfc3339(at)
+                .map_err(|err| {
+                    format!(
+                        "'deliver_at' must be an RFC 3339 time like 2025-01-31T17:00:00Z: {err}"
+                    )
+                })?
+                .timestamp_millis();
+            let at = u64::try_from(at).unwrap_or_default();
+            if at <= now {
+                return Err("'deliver_at' is in the past".into());
+            }
+            if at - now > MAX_DELAY_SECS * 1000 {
+                return Err("'deliver_at' is more than a year away".into());
+            }
+            Ok(Some(at))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ScheduledNotification {
+    /// Also the id of the notification once it is delivered.
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub agent: String,
+    #[serde(default)]
+    pub level: NotificationLevel,
+    #[serde(default)]
+    pub silent: bool,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<NotificationAction>,
+    #[serde(default)]
+    pub source: NotificationSource,
+    /// MCP session to tell about action results; sessions do not outlive the app.
+    #[serde(skip)]
+    pub reply_to: Option<String>,
+    /// Unix timestamp in milliseconds.
+    pub deliver_at: u64,
+    /// Unix timestamp in milliseconds.
+    pub created_at: u64,
+}
+
+impl ScheduledNotification {
+    pub fn new(notification: Notification, deliver_at: u64, now: u64) -> Self {
+        let Notification {
+            title,
+            content,
+            agent,
+            level,
+            silent,
+            source,
+            url,
+            actions,
+            reply_to,
+        } = notification;
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            title,
+            content,
+            agent,
+            level,
+            silent,
+            url,
+            actions,
+            source,
+            reply_to,
+            deliver_at,
+            created_at: now,
+        }
+    }
+
+    /// The notification to deliver now, saying when it was due if it is late.
+    pub fn notification(&self, now: u64) -> Notification {
+        let mut content = self.content.clone();
+        if now.saturating_sub(self.deliver_at) > LATE_AFTER_MS {
+            let due = DateTime::from_timestamp_millis(self.deliver_at as i64)
+                .map(|due| {
+                    due.with_timezone(&Local)
+                        .format("%b %-d, %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            content.push_str(&format!("\n(Delivered late; it was due {due}.)"));
+        }
+        Notification {
+            title: self.title.clone(),
+            content,
+            agent: self.agent.clone(),
+            level: self.level,
+            silent: self.silent,
+            source: self.source,
+            url: self.url.clone(),
+            actions: self.actions.clone(),
+            reply_to: self.reply_to.clone(),
+        }
+    }
+}
+
+/// Notifications waiting for their delivery time.
+pub(crate) struct Scheduler {
+    pending: Mutex<Vec<ScheduledNotification>>,
+}
+
+impl Scheduler {
+    pub fn new(pending: Vec<ScheduledNotification>) -> Self {
+        Self {
+            pending: Mutex::new(pending),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<ScheduledNotification>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn add(&self, scheduled: ScheduledNotification) -> Result<(), String> {
+        let mut pending = self.lock();
+        if pending.len() >= MAX_SCHEDULED {
+            return Err(format!(
+                "At most {MAX_SCHEDULED} notifications can be scheduled at once"
+            ));
+        }
+        pending.push(scheduled);
+        Ok(())
+    }
+
+    /// Scheduled notifications, soonest first.
+    pub fn list(&self) -> Vec<ScheduledNotification> {
+        let mut pending = self.lock().clone();
+        pending.sort_by_key(|scheduled| scheduled.deliver_at);
+        pending
+    }
+
+    /// Cancels a scheduled notification; `owner` limits it to ones from that agent.
+    pub fn cancel(&self, id: &str, owner: Option<&str>) -> Option<ScheduledNotification> {
+        let mut pending = self.lock();
+        let index = pending.iter().position(|scheduled| {
+            scheduled.id == id && owner.is_none_or(|owner| scheduled.agent == owner)
+        })?;
+        Some(pending.remove(index))
+    }
+
+    /// Everything due by `now`, oldest first. They stay scheduled until [`Self::complete`], so
+    /// a crash before delivery does not lose them.
+    pub fn due(&self, now: u64) -> Vec<ScheduledNotification> {
+        let mut due: Vec<_> = self
+            .lock()
+            .iter()
+            .filter(|scheduled| scheduled.deliver_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|scheduled| scheduled.deliver_at);
+        due
+    }
+
+    /// Removes a notification once it has been dispatched.
+    pub fn complete(&self, id: &str) -> Option<ScheduledNotification> {
+        let mut pending = self.lock();
+        let index = pending.iter().position(|scheduled| scheduled.id == id)?;
+        Some(pending.remove(index))
+    }
+}
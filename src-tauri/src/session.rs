@@ -0,0 +1,321 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, Weak};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, RwLock};
+
+pub(crate) const SESSION_HEADER: &str = "mcp-session-id";
+// Agents that have not been heard from in this long are treated as gone.
+const SESSION_IDLE_TIMEOUT_MS: u64 = 30 * 60 * 1000;
+// Events kept per session so a reconnecting client can resume with `Last-Event-ID`.
+const STREAM_BACKLOG: usize = 100;
+
+/// Syslog-style severities used by `logging/setLevel` and `notifications/message`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct McpSession {
+    pub id: String,
+    pub client_name: Option<String>,
+    pub client_version: Option<String>,
+    /// Revision negotiated during `initialize`; shapes every later response.
+    pub protocol_version: String,
+    /// Minimum severity forwarded as `notifications/message`.
+    pub log_level: LogLevel,
+    /// Unix timestamp in milliseconds.
+    pub created_at: u64,
+    /// Unix timestamp in milliseconds.
+    pub last_seen: u64,
+}
+
+impl McpSession {
+    fn is_expired(&self, now: u64) -> bool {
+        now.saturating_sub(self.last_seen) > SESSION_IDLE_TIMEOUT_MS
+    }
+}
+
+pub(crate) enum SessionLookup {
+    Missing,
+    Unknown,
+    Active(McpSession),
+}
+
+/// A JSON-RPC message queued for the session's `GET /mcp` stream.
+#[derive(Debug, Clone)]
+pub(crate) struct StreamEvent {
+    pub id: u64,
+    pub message: Value,
+}
+
+struct Backlog {
+    next_id: u64,
+    events: VecDeque<StreamEvent>,
+    /// Highest event id handed to a stream so far.
+    delivered: u64,
+}
+
+/// Server-to-client channel for one session.
+///
+/// The backlog lock is held while sending and while subscribing, so a new subscriber sees
+/// every event exactly once: either in its replay or on its receiver.
+struct SessionChannel {
+    sender: broadcast::Sender<StreamEvent>,
+    backlog: Mutex<Backlog>,
+}
+
+impl SessionChannel {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(STREAM_BACKLOG);
+        Self {
+            sender,
+            backlog: Mutex::new(Backlog {
+                next_id: 1,
+                events: VecDeque::new(),
+                delivered: 0,
+            }),
+        }
+    }
+
+    fn send(&self, message: Value) {
+        let mut backlog = self.backlog.lock().unwrap_or_else(|e| e.into_inner());
+        let event = StreamEvent {
+            id: backlog.next_id,
+            message,
+        };
+        backlog.next_id += 1;
+        backlog.events.push_back(event.clone());
+        while backlog.events.len() > STREAM_BACKLOG {
+            backlog.events.pop_front();
+        }
+        // No receivers just means no stream is open; the backlog still has the event.
+        let _ = self.sender.send(event);
+    }
+
+    /// Without `last_event_id`, replays whatever no stream has delivered yet, such as events
+    /// sent before the client's first `GET /mcp`.
+    fn subscribe(
+        &self,
+        last_event_id: Option<u64>,
+    ) -> (Vec<StreamEvent>, broadcast::Receiver<StreamEvent>) {
+        let backlog = self.backlog.lock().unwrap_or_else(|e| e.into_inner());
+        let receiver = self.sender.subscribe();
+        let after = last_event_id.unwrap_or(backlog.delivered);
+        let replay = backlog
+            .events
+            .iter()
+            .filter(|event| event.id > after)
+            .cloned()
+            .collect();
+        (replay, receiver)
+    }
+
+    fn mark_delivered(&self, id: u64) {
+        let mut backlog = self.backlog.lock().unwrap_or_else(|e| e.into_inner());
+        backlog.delivered = backlog.delivered.max(id);
+    }
+}
+
+/// Records which events a session's stream has handed to the client.
+///
+/// Holds the channel weakly: an open stream must not keep its sender alive, or removing the
+/// session would never close the stream.
+pub(crate) struct DeliveryCursor(Weak<SessionChannel>);
+
+impl DeliveryCursor {
+    pub fn delivered(&self, id: u64) {
+        if let Some(channel) = self.0.upgrade() {
+            channel.mark_delivered(id);
+        }
+    }
+}
+
+struct SessionEntry {
+    info: McpSession,
+    channel: Arc<SessionChannel>,
+}
+
+#[derive(Default)]
+pub(crate) struct SessionRegistry {
+    sessions: RwLock<HashMap<String, SessionEntry>>,
+}
+
+impl SessionRegistry {
+    /// Starts a session from the `clientInfo` object sent with `initialize`.
+    pub async fn create(&self, client_info: Option<&Value>, protocol_version: &str) -> McpSession {
+        let field = |name: &str| {
+            client_info
+                .and_then(|info| info.get(name))
+                .and_then(Value::as_str)
+                .map(str::to_owned)
+        };
+        let now = crate::unix_millis_now();
+        let session = McpSession {
+            id: uuid::Uuid::new_v4().to_string(),
+            client_name: field("name"),
+            client_version: field("version"),
+            protocol_version: protocol_version.to_owned(),
+            log_level: LogLevel::default(),
+            created_at: now,
+            last_seen: now,
+        };
+
+        let mut sessions = self.sessions.write().await;
+        sessions.retain(|_, existing| !existing.info.is_expired(now));
+        sessions.insert(
+            session.id.clone(),
+            SessionEntry {
+                info: session.clone(),
+                channel: Arc::new(SessionChannel::new()),
+            },
+        );
+        session
+    }
+
+    /// Resolves the session named by a request header and marks it as seen.
+    pub async fn touch(&self, id: Option<&str>) -> SessionLookup {
+        let Some(id) = id else {
+            return SessionLookup::Missing;
+        };
+
+        let now = crate::unix_millis_now();
+        let mut sessions = self.sessions.write().await;
+        match sessions.get_mut(id) {
+            Some(entry) if entry.info.is_expired(now) => {
+                sessions.remove(id);
+                SessionLookup::Unknown
+            }
+            Some(entry) => {
+                entry.info.last_seen = now;
+                SessionLookup::Active(entry.info.clone())
+            }
+            None => SessionLookup::Unknown,
+        }
+    }
+
+    /// Dropping the entry closes the session's channel, which ends any open stream.
+    pub async fn remove(&self, id: &str) -> bool {
+        self.sessions.write().await.remove(id).is_some()
+    }
+
+    /// Active sessions, most recently seen first.
+    pub async fn list(&self) -> Vec<McpSession> {
+        let now = crate::unix_millis_now();
+        let mut sessions = self.sessions.write().await;
+        sessions.retain(|_, entry| !entry.info.is_expired(now));
+
+        let mut active: Vec<McpSession> =
+            sessions.values().map(|entry| entry.info.clone()).collect();
+        active.sort_by_key(|session| std::cmp::Reverse(session.last_seen));
+        active
+    }
+
+    pub async fn set_log_level(&self, id: &str, level: LogLevel) {
+        if let Some(entry) = self.sessions.write().await.get_mut(id) {
+            entry.info.log_level = level;
+        }
+    }
+
+    /// Opens the session's event stream, replaying anything after `last_event_id`, or
+    /// everything not yet delivered when it is unset.
+    pub async fn subscribe(
+        &self,
+        id: &str,
+        last_event_id: Option<u64>,
+    ) -> Option<(
+        Vec<StreamEvent>,
+        broadcast::Receiver<StreamEvent>,
+        DeliveryCursor,
+    )> {
+        let channel = self.sessions.read().await.get(id)?.channel.clone();
+        let (replay, receiver) = channel.subscribe(last_event_id);
+        Some((replay, receiver, DeliveryCursor(Arc::downgrade(&channel))))
+    }
+
+    /// Queues a JSON-RPC notification for every active session.
+    pub async fn broadcast(&self, method: &str, params: Value) {
+        let message = notification(method, params);
+        for entry in self.sessions.read().await.values() {
+            entry.channel.send(message.clone());
+        }
+    }
+
+    /// Queues a JSON-RPC notification for one session, if it is still around.
+    pub async fn notify(&self, id: &str, method: &str, params: Value) {
+        if let Some(entry) = self.sessions.read().await.get(id) {
+            entry.channel.send(notification(method, params));
+        }
+    }
+
+    /// Sends a `notifications/message` log entry if the session asked for this severity.
+    pub async fn log(&self, id: &str, level: LogLevel, data: Value) {
+        let channel = {
+            let sessions = self.sessions.read().await;
+            match sessions.get(id) {
+                Some(entry) if level >= entry.info.log_level => entry.channel.clone(),
+                _ => return,
+            }
+        };
+        channel.send(notification(
+            "notifications/message",
+            json!({ "level": level, "logger": "agent-notifier", "data": data }),
+        ));
+    }
+}
+
+pub(crate) fn notification(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::broadcast::error::RecvError;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn removing_a_session_ends_its_stream() {
+        let registry = SessionRegistry::default();
+        let session = registry.create(None, "2025-06-18").await;
+        let (_, mut receiver, cursor) = registry.subscribe(&session.id, None).await.unwrap();
+
+        assert!(registry.remove(&session.id).await);
+        assert!(matches!(receiver.recv().await, Err(RecvError::Closed)));
+        // A stream still draining after removal must not bring the channel back.
+        cursor.delivered(1);
+        assert!(registry.subscribe(&session.id, None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn first_stream_replays_events_sent_before_it_opened() {
+        let registry = SessionRegistry::default();
+        let session = registry.create(None, "2025-06-18").await;
+        registry.notify(&session.id, "early", json!({})).await;
+
+        let (replay, _receiver, cursor) = registry.subscribe(&session.id, None).await.unwrap();
+        assert_eq!(replay.len(), 1);
+        assert_eq!(replay[0].message["method"], "early");
+        cursor.delivered(replay[0].id);
+
+        let (replay, _, _) = registry.subscribe(&session.id, None).await.unwrap();
+        assert!(replay.is_empty());
+        let (replay, _, _) = registry.subscribe(&session.id, Some(0)).await.unwrap();
+        assert_eq!(replay.len(), 1);
+    }
+}
@@ -0,0 +1,109 @@
+use std::{collections::BTreeMap, process::Stdio, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+// Sinks run in the background, but a hung endpoint or script should not pile up forever.
+const SINK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Somewhere besides the desktop that a notification can be sent to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Sink {
+    /// POSTs the notification as JSON.
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Runs a program with the notification as JSON on stdin.
+    Exec {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl Sink {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Webhook { url, .. } => {
+                let parsed = reqwest::Url::parse(url)
+                    .map_err(|err| format!("'{url}' is not a valid webhook URL: {err}"))?;
+                if !matches!(parsed.scheme(), "http" | "https") {
+                    return Err(format!("Webhook URL '{url}' must use http or https"));
+                }
+            }
+            Self::Exec { command, .. } => {
+                if command.trim().is_empty() {
+                    return Err("Exec sinks need a command".into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn deliver(&self, payload: &Value) -> Result<(), String> {
+        match tokio::time::timeout(SINK_TIMEOUT, self.send(payload)).await {
+            Ok(result) => result,
+            Err(_) => Err(format!(
+                "{} timed out after {}s",
+                self.describe(),
+                SINK_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    async fn send(&self, payload: &Value) -> Result<(), String> {
+        match self {
+            Self::Webhook { url, headers } => {
+                let mut request = reqwest::Client::new().post(url).json(payload);
+                for (name, value) in headers {
+                    request = request.header(name, value);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|err| format!("{} failed: {err}", self.describe()))?;
+                if !response.status().is_success() {
+                    return Err(format!(
+                        "{} answered {}",
+                        self.describe(),
+                        response.status()
+                    ));
+                }
+                Ok(())
+            }
+            Self::Exec { command, args } => {
+                let mut child = Command::new(command)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .map_err(|err| format!("Failed to start {}: {err}", self.describe()))?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    // A script that ignores stdin closes the pipe early; that is not an error.
+                    let _ = stdin.write_all(payload.to_string().as_bytes()).await;
+                }
+                let status = child
+                    .wait()
+                    .await
+                    .map_err(|err| format!("{} failed: {err}", self.describe()))?;
+                if !status.success() {
+                    return Err(format!("{} exited with {status}", self.describe()));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Webhook { url, .. } => format!("Webhook {url}"),
+            Self::Exec { command, .. } => format!("Command '{command}'"),
+        }
+    }
+}
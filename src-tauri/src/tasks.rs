@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::history::NotificationSource;
+use crate::levels::NotificationLevel;
+use crate::Notification;
+
+pub(crate) const TASKS_CHANGED_EVENT: &str = "tasks-changed";
+// Tasks that never finish would otherwise pile up; the oldest are forgotten first.
+const MAX_RUNNING_TASKS: usize = 200;
+const MAX_TASK_ID_CHARS: usize = 128;
+const MAX_DEADLINE_SECS: u64 = 7 * 24 * 60 * 60;
+// The watchdog only looks every few seconds, so shorter heartbeats would just be noise.
+const MIN_HEARTBEAT_SECS: u64 = 10;
+const MAX_HEARTBEAT_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TaskOutcome {
+    #[default]
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl TaskOutcome {
+    pub const NAMES: [&'static str; 3] = ["succeeded", "failed", "cancelled"];
+
+    /// Level of the finish notification when the caller does not pick one.
+    pub fn level(self) -> NotificationLevel {
+        match self {
+            Self::Succeeded => NotificationLevel::Success,
+            Self::Failed => NotificationLevel::Error,
+            Self::Cancelled => NotificationLevel::Warning,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Succeeded => "Succeeded",
+            Self::Failed => "Failed",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RunningTask {
+    /// Chosen by the agent; unique per agent.
+    pub id: String,
+    pub agent: String,
+    pub title: String,
+    /// Unix timestamp in milliseconds.
+    pub started_at: u64,
+    /// When the task is expected to be done, unix milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline_at: Option<u64>,
+    /// A warning is raised when no heartbeat arrives for this long.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval_seconds: Option<u64>,
+    /// The last heartbeat, or the start; unix milliseconds.
+    pub last_heartbeat_at: u64,
+    /// The deadline passed and the user was warned.
+    pub overdue: bool,
+    /// Heartbeats stopped and the user was warned; cleared by the next heartbeat.
+    pub stalled: bool,
+    /// Where the task was started, for the history of its notifications.
+    #[serde(skip)]
+    pub source: NotificationSource,
+}
+
+/// What the watchdog noticed about a running task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Alarm {
+    Overdue,
+    Stalled,
+}
+
+impl RunningTask {
+    /// The warning shown when the watchdog raises `alarm`.
+    pub fn alarm_notification(&self, alarm: Alarm, now: u64) -> Notification {
+        let (title, content) = match alarm {
+            Alarm::Overdue => (
+                format!("{} is overdue", self.title),
+                format!(
+                    "Still running after {}; it was expected to take {}.",
+                    format_duration(now.saturating_sub(self.started_at)),
+                    format_duration(
+                        self.deadline_at
+                            .unwrap_or(now)
+                            .saturating_sub(self.started_at)
+                    )
+                ),
+            ),
+            Alarm::Stalled => (
+                format!("{} went quiet", self.title),
+                format!(
+                    "No heartbeat from {} for {}. It may have crashed or hung.",
+                    self.agent,
+                    format_duration(now.saturating_sub(self.last_heartbeat_at))
+                ),
+            ),
+        };
+        Notification {
+            title,
+            content,
+            agent: self.agent.clone(),
+            level: NotificationLevel::Warning,
+            silent: false,
+            source: self.source,
+            url: None,
+            actions: Vec::new(),
+            reply_to: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FinishedTask {
+    #[serde(flatten)]
+    pub task: RunningTask,
+    /// Unix timestamp in milliseconds.
+    pub finished_at: u64,
+    pub duration_ms: u64,
+    pub outcome: TaskOutcome,
+}
+
+impl FinishedTask {
+    /// The notification announcing the finish, with the elapsed time filled in.
+    pub fn notification(
+        &self,
+        summary: Option<&str>,
+        level: Option<NotificationLevel>,
+        silent: bool,
+        source: NotificationSource,
+    ) -> Notification {
+        let mut content = format!(
+            "{} after {}.",
+            self.outcome.describe(),
+            format_duration(self.duration_ms)
+        );
+        if let Some(summary) = summary.map(str::trim).filter(|summary| !summary.is_empty()) {
+            content.push(' ');
+            content.push_str(summary);
+        }
+        Notification {
+            title: self.task.title.clone(),
+            content,
+            agent: self.task.agent.clone(),
+            level: level.unwrap_or_else(|| self.outcome.level()),
+            silent,
+            source,
+            url: None,
+            actions: Vec::new(),
+            reply_to: None,
+        }
+    }
+}
+
+pub(crate) fn validate_task_id(id: &str) -> Result<String, String> {
+    let id = id.trim();
+    if id.is_empty() || id.chars().count() > MAX_TASK_ID_CHARS {
+        return Err(format!("'id' must be 1 to {MAX_TASK_ID_CHARS} characters"));
+    }
+    Ok(id.to_owned())
+}
+
+/// Checks the optional deadline and heartbeat interval a task can be started with.
+pub(crate) fn validate_watchdog(
+    deadline_seconds: Option<u64>,
+    heartbeat_seconds: Option<u64>,
+) -> Result<(), String> {
+    if deadline_seconds.is_some_and(|secs| secs == 0 || secs > MAX_DEADLINE_SECS) {
+        return Err(format!(
+            "'deadline_seconds' must be between 1 and {MAX_DEADLINE_SECS}"
+        ));
+    }
+    if heartbeat_seconds
+        .is_some_and(|secs| !(MIN_HEARTBEAT_SECS..=MAX_HEARTBEAT_SECS).contains(&secs))
+    {
+        return Err(format!(
+            "'heartbeat_seconds' must be between {MIN_HEARTBEAT_SECS} and {MAX_HEARTBEAT_SECS}"
+        ));
+    }
+    Ok(())
+}
+
+/// Tasks agents have started and not yet finished, keyed by agent and task id.
+#[derive(Default)]
+pub(crate) struct TaskTracker {
+    tasks: Mutex<HashMap<(String, String), RunningTask>>,
+}
+
+impl TaskTracker {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(String, String), RunningTask>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Starts the clock on a task. Starting one that is already running restarts its clock;
+    /// returns whether that happened.
+    pub fn start(&self, app: &AppHandle, task: RunningTask) -> bool {
+        let restarted = {
+            let mut tasks = self.lock();
+            let restarted = tasks
+                .insert((task.agent.clone(), task.id.clone()), task)
+                .is_some();
+            while tasks.len() > MAX_RUNNING_TASKS {
+                let Some(oldest) = tasks
+                    .iter()
+                    .min_by_key(|(_, task)| task.started_at)
+                    .map(|(key, _)| key.clone())
+                else {
+                    break;
+                };
+                tasks.remove(&oldest);
+            }
+            restarted
+        };
+        self.changed(app);
+        restarted
+    }
+
+    pub fn finish(
+        &self,
+        app: &AppHandle,
+        agent: &str,
+        id: &str,
+        outcome: TaskOutcome,
+        now: u64,
+    ) -> Option<FinishedTask> {
+        let task = self.lock().remove(&(agent.to_owned(), id.to_owned()))?;
+        self.changed(app);
+        Some(FinishedTask {
+            duration_ms: now.saturating_sub(task.started_at),
+            task,
+            finished_at: now,
+            outcome,
+        })
+    }
+
+    /// Records a sign of life; returns the task, or `None` if it is not running.
+    pub fn heartbeat(
+        &self,
+        app: &AppHandle,
+        agent: &str,
+        id: &str,
+        now: u64,
+    ) -> Option<RunningTask> {
+        let (task, was_stalled) = {
+            let mut tasks = self.lock();
+            let task = tasks.get_mut(&(agent.to_owned(), id.to_owned()))?;
+            let was_stalled = std::mem::take(&mut task.stalled);
+            task.last_heartbeat_at = now;
+            (task.clone(), was_stalled)
+        };
+        // Heartbeats can be frequent; only redraw when the tray label changes.
+        if was_stalled {
+            self.changed(app);
+        }
+        Some(task)
+    }
+
+    /// Marks and returns every task that has just passed its deadline or stopped sending
+    /// heartbeats. Each alarm goes off once; a heartbeat re-arms the stalled one.
+    pub fn take_alarms(&self, app: &AppHandle, now: u64) -> Vec<(RunningTask, Alarm)> {
+        let alarms: Vec<(RunningTask, Alarm)> = {
+            let mut tasks = self.lock();
+            let mut alarms = Vec::new();
+            for task in tasks.values_mut() {
+                if !task.overdue && task.deadline_at.is_some_and(|deadline| deadline <= now) {
+                    task.overdue = true;
+                    alarms.push((task.clone(), Alarm::Overdue));
+                }
+                let silence = now.saturating_sub(task.last_heartbeat_at);
+                if !task.stalled
+                    && task
+                        .heartbeat_interval_seconds
+                        .is_some_and(|secs| silence >= secs * 1000)
+                {
+                    task.stalled = true;
+                    alarms.push((task.clone(), Alarm::Stalled));
+                }
+            }
+            alarms
+        };
+        if !alarms.is_empty() {
+            self.changed(app);
+        }
+        alarms
+    }
+
+    /// Running tasks, oldest first.
+    pub fn list(&self) -> Vec<RunningTask> {
+        let mut tasks: Vec<RunningTask> = self.lock().values().cloned().collect();
+        tasks.sort_by_key(|task| task.started_at);
+        tasks
+    }
+
+    fn changed(&self, app: &AppHandle) {
+        if let Err(err) = app.emit(TASKS_CHANGED_EVENT, ()) {
+            eprintln!("Failed to emit {TASKS_CHANGED_EVENT}: {err}");
+        }
+        #[cfg(desktop)]
+        crate::sync_tray_tasks(app, &self.list());
+    }
+}
+
+/// Formats a duration the way people say it: "45s", "3m 12s", "2h 05m".
+pub(crate) fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        0 => format!("{ms}ms"),
+        1..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
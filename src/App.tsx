@@ -2,26 +2,76 @@ import { useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import {
   ArrowLeft,
+  Bell,
+  BellRing,
   ChevronRight,
+  Clock,
+  Eye,
+  Filter,
+  Gauge,
+  Layers,
   Loader2,
+  MessageSquare,
+  Moon,
   Network,
   Server,
+  Volume2,
 } from "lucide-react";
 
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
+import { CoalesceSettingsForm } from "@/components/coalesce-settings";
+import { EscalationPanel } from "@/components/escalation-panel";
+import { EscalationSettingsForm } from "@/components/escalation-settings";
+import { LevelSettingsForm } from "@/components/level-settings";
+import { ActionPanel } from "@/components/action-panel";
+import { FileWatchesForm } from "@/components/file-watch-settings";
+import { PromptPanel } from "@/components/prompt-panel";
+import { RateLimitSettingsForm } from "@/components/rate-limit-settings";
+import { QuietSettingsForm } from "@/components/quiet-settings";
+import { RuleSettingsForm } from "@/components/rule-settings";
+import { ScheduledNotificationsList } from "@/components/scheduled-list";
+import { SoundSettingsForm } from "@/components/sound-settings";
 
-type View = "root" | "http";
+type View =
+  | "root"
+  | "http"
+  | "levels"
+  | "sounds"
+  | "quiet"
+  | "rules"
+  | "repeats"
+  | "limits"
+  | "watches"
+  | "scheduled"
+  | "escalation";
 
 type HttpSettings = {
   bind_address: string;
   port: number;
+  allowed_origins: string[];
+  allowed_hosts: string[];
 };
 
+type FormField = "bind_address" | "port" | "allowed_origins" | "allowed_hosts";
+
 const DEFAULT_SETTINGS: HttpSettings = {
   bind_address: "127.0.0.1",
   port: 60766,
+  allowed_origins: [],
+  allowed_hosts: [],
+};
+
+function splitList(value: string): string[] {
+  return value
+    .split(",")
+    .map((entry) => entry.trim())
+    .filter(Boolean);
+}
+
+type McpToolSettings = {
+  ask_user_enabled: boolean;
 };
 
 type Status = { type: "success" | "error"; message: string } | null;
@@ -31,10 +81,15 @@ function App() {
   const [form, setForm] = useState({
     bind_address: DEFAULT_SETTINGS.bind_address,
     port: DEFAULT_SETTINGS.port.toString(),
+    allowed_origins: "",
+    allowed_hosts: "",
   });
   const [loading, setLoading] = useState(true);
   const [saving, setSaving] = useState(false);
   const [status, setStatus] = useState<Status>(null);
+  const [mcpTools, setMcpTools] = useState<McpToolSettings>({
+    ask_user_enabled: true,
+  });
 
   const bindingPreview = useMemo(
     () => `${form.bind_address || "—"}:${form.port || "—"}`,
@@ -43,8 +98,21 @@ function App() {
 
   useEffect(() => {
     loadSettings();
+    invoke<McpToolSettings>("get_mcp_tool_settings")
+      .then(setMcpTools)
+      .catch(console.error);
   }, []);
 
+  async function toggleAskUser() {
+    const next = { ...mcpTools, ask_user_enabled: !mcpTools.ask_user_enabled };
+    try {
+      await invoke("save_mcp_tool_settings", { settings: next });
+      setMcpTools(next);
+    } catch (err) {
+      console.error(err);
+    }
+  }
+
   async function loadSettings() {
     setLoading(true);
     setStatus(null);
@@ -53,6 +121,8 @@ function App() {
       setForm({
         bind_address: result.bind_address,
         port: result.port.toString(),
+        allowed_origins: result.allowed_origins.join(", "),
+        allowed_hosts: result.allowed_hosts.join(", "),
       });
     } catch (err) {
       console.error(err);
@@ -65,7 +135,7 @@ function App() {
     }
   }
 
-  function updateField(key: "bind_address" | "port", value: string) {
+  function updateField(key: FormField, value: string) {
     setForm((prev) => ({ ...prev, [key]: value }));
     setStatus(null);
   }
@@ -86,7 +156,12 @@ function App() {
     setSaving(true);
     try {
       await invoke("save_http_bindings", {
-        settings: { bind_address: trimmedAddress, port: parsedPort },
+        settings: {
+          bind_address: trimmedAddress,
+          port: parsedPort,
+          allowed_origins: splitList(form.allowed_origins),
+          allowed_hosts: splitList(form.allowed_hosts),
+        },
       });
       setStatus({ type: "success", message: "HTTP bindings saved" });
     } catch (err) {
@@ -101,6 +176,8 @@ function App() {
     setForm({
       bind_address: DEFAULT_SETTINGS.bind_address,
       port: DEFAULT_SETTINGS.port.toString(),
+      allowed_origins: "",
+      allowed_hosts: "",
     });
     setStatus(null);
   }
@@ -108,6 +185,9 @@ function App() {
   return (
     <main className="min-h-screen bg-background text-foreground">
       <div className="mx-auto max-w-3xl px-6 pt-6 pb-6">
+        <PromptPanel />
+        <EscalationPanel />
+        <ActionPanel />
         <div className="pt-2">
           {view === "root" ? (
             <div className="space-y-3">
@@ -126,7 +206,361 @@ function App() {
                     <ChevronRight className="h-4 w-4" />
                   </div>
                 </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("levels")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Bell className="h-4 w-4" />
+                    <p className="text-sm font-medium">Notification levels</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("sounds")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Volume2 className="h-4 w-4" />
+                    <p className="text-sm font-medium">Sounds</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("quiet")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Moon className="h-4 w-4" />
+                    <p className="text-sm font-medium">Do Not Disturb</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("rules")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Filter className="h-4 w-4" />
+                    <p className="text-sm font-medium">Rules</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("repeats")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Layers className="h-4 w-4" />
+                    <p className="text-sm font-medium">Repeats and bursts</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("limits")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Gauge className="h-4 w-4" />
+                    <p className="text-sm font-medium">Rate limits</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("escalation")}
+                >
+                  <div className="flex items-center gap-3">
+                    <BellRing className="h-4 w-4" />
+                    <p className="text-sm font-medium">Escalation</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("watches")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Eye className="h-4 w-4" />
+                    <p className="text-sm font-medium">File watches</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <button
+                  className="flex w-full items-center justify-between px-4 py-3 text-left transition-colors hover:bg-accent hover:text-accent focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring"
+                  onClick={() => setView("scheduled")}
+                >
+                  <div className="flex items-center gap-3">
+                    <Clock className="h-4 w-4" />
+                    <p className="text-sm font-medium">Scheduled notifications</p>
+                  </div>
+                  <ChevronRight className="h-4 w-4 text-muted" />
+                </button>
+                <label className="flex w-full cursor-pointer items-center justify-between px-4 py-3 text-left">
+                  <div className="flex items-center gap-3">
+                    <MessageSquare className="h-4 w-4" />
+                    <p className="text-sm font-medium">
+                      Allow agents to ask questions
+                    </p>
+                  </div>
+                  <input
+                    type="checkbox"
+                    checked={mcpTools.ask_user_enabled}
+                    onChange={toggleAskUser}
+                    className="h-4 w-4 accent-current"
+                  />
+                </label>
+              </div>
+            </div>
+          ) : view === "levels" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Notification levels
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  Notification levels
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Agents can tag notifications as info, success, warning, error
+                  or critical. Pick the title prefix, sound and urgency each
+                  level gets.
+                </p>
+              </div>
+
+              <LevelSettingsForm />
+            </div>
+          ) : view === "sounds" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Sounds
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  Sounds
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Use a built-in sound or any .wav, .ogg, .flac or .mp3 file.
+                  An agent's sound wins over its notification level's sound.
+                </p>
+              </div>
+
+              <SoundSettingsForm />
+            </div>
+          ) : view === "quiet" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Do Not Disturb
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  Do Not Disturb
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Notifications that arrive during quiet hours or a pause are
+                  held back and shown as one summary afterwards. Levels with
+                  critical urgency always get through.
+                </p>
+              </div>
+
+              <QuietSettingsForm />
+            </div>
+          ) : view === "rules" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Rules
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">Rules</h2>
+                <p className="text-sm text-muted-foreground">
+                  Rules run on every notification before it is shown, top to
+                  bottom; the first one that matches applies. Use them to mute
+                  noisy agents, change sounds or titles, forward to a webhook
+                  or command, or raise the level.
+                </p>
+              </div>
+
+              <RuleSettingsForm />
+            </div>
+          ) : view === "repeats" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Repeats and bursts
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  Repeats and bursts
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Drop notifications an agent sends again while retrying, and
+                  fold a flurry from one agent into a single summary such as
+                  "3 more updates from codex".
+                </p>
+              </div>
+
+              <CoalesceSettingsForm />
+            </div>
+          ) : view === "limits" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Rate limits
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  Rate limits
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Caps how fast agents can send notifications, so a runaway
+                  loop cannot flood the desktop. Set a rate to 0 to remove
+                  that limit.
+                </p>
+              </div>
+
+              <RateLimitSettingsForm />
+            </div>
+          ) : view === "watches" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / File watches
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  File watches
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Get a notification when a file appears or changes, or when a
+                  new line in a log matches a pattern. Watches are checked
+                  every couple of seconds and kept across restarts.
+                </p>
+              </div>
+
+              <FileWatchesForm />
+            </div>
+          ) : view === "scheduled" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Scheduled notifications
+                </div>
               </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  Scheduled notifications
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Reminders agents have set for later. Anything that falls due
+                  while the app is closed is delivered when it next starts.
+                </p>
+              </div>
+
+              <ScheduledNotificationsList />
+            </div>
+          ) : view === "escalation" ? (
+            <div className="space-y-5">
+              <div className="flex items-center gap-3">
+                <button
+                  className="inline-flex items-center gap-2 rounded-full bg-muted px-3 py-1 text-sm text-foreground transition-colors hover:bg-muted/80"
+                  onClick={() => setView("root")}
+                >
+                  <ArrowLeft className="h-4 w-4" />
+                  Back
+                </button>
+                <div className="text-sm text-muted-foreground">
+                  General / Escalation
+                </div>
+              </div>
+
+              <div className="space-y-1">
+                <h2 className="text-xl font-semibold text-foreground">
+                  Escalation
+                </h2>
+                <p className="text-sm text-muted-foreground">
+                  Notifications a policy covers stay on screen until you
+                  acknowledge them. Until then they come back at each interval,
+                  louder every time, and once the reminders run out they are
+                  handed to a webhook or command, for example to page someone.
+                </p>
+              </div>
+
+              <EscalationSettingsForm />
             </div>
           ) : (
             <div className="space-y-5">
@@ -206,6 +640,56 @@ function App() {
                     />
                   </div>
                 </div>
+
+                <div className="flex flex-wrap items-center justify-between gap-4 px-4 py-4">
+                  <div className="space-y-1">
+                    <Label htmlFor="allowed-origins" className="text-foreground">
+                      Allowed origins
+                    </Label>
+                    <p className="text-xs text-muted-foreground">
+                      Comma-separated. Loopback origins are always allowed.
+                    </p>
+                  </div>
+                  <div className="flex min-w-[220px] flex-1 items-center gap-3 sm:max-w-sm">
+                    <div className="flex h-9 w-9 items-center justify-center rounded-full bg-muted text-foreground">
+                      <Server className="h-4 w-4" />
+                    </div>
+                    <Input
+                      id="allowed-origins"
+                      value={form.allowed_origins}
+                      onChange={(e) =>
+                        updateField("allowed_origins", e.currentTarget.value)
+                      }
+                      placeholder="https://dashboard.example.com"
+                      className="flex-1 bg-muted/40 text-foreground placeholder:text-muted-foreground"
+                    />
+                  </div>
+                </div>
+
+                <div className="flex flex-wrap items-center justify-between gap-4 px-4 py-4">
+                  <div className="space-y-1">
+                    <Label htmlFor="allowed-hosts" className="text-foreground">
+                      Allowed hosts
+                    </Label>
+                    <p className="text-xs text-muted-foreground">
+                      Host names remote agents use to reach this machine.
+                    </p>
+                  </div>
+                  <div className="flex min-w-[220px] flex-1 items-center gap-3 sm:max-w-sm">
+                    <div className="flex h-9 w-9 items-center justify-center rounded-full bg-muted text-foreground">
+                      <Server className="h-4 w-4" />
+                    </div>
+                    <Input
+                      id="allowed-hosts"
+                      value={form.allowed_hosts}
+                      onChange={(e) =>
+                        updateField("allowed_hosts", e.currentTarget.value)
+                      }
+                      placeholder="my-laptop.local"
+                      className="flex-1 bg-muted/40 text-foreground placeholder:text-muted-foreground"
+                    />
+                  </div>
+                </div>
               </div>
 
               {status && (
@@ -0,0 +1,217 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Bell, ExternalLink, Loader2, X } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+
+type NotificationAction = { id: string; label: string } & (
+  | { type: "open_url"; url: string }
+  | { type: "open_path"; path: string }
+  | { type: "copy"; text: string }
+  | { type: "reply"; allow_text: boolean }
+);
+
+type ActionResult = {
+  action_id: string;
+  label: string;
+  text: string | null;
+  at: number;
+};
+
+type ActionableNotification = {
+  id: string;
+  agent: string;
+  title: string;
+  content: string;
+  url: string | null;
+  actions: NotificationAction[];
+  created_at: number;
+  results: ActionResult[];
+  dismissed: boolean;
+};
+
+export function ActionPanel() {
+  const [notifications, setNotifications] = useState<ActionableNotification[]>(
+    [],
+  );
+
+  useEffect(() => {
+    function load() {
+      invoke<ActionableNotification[]>("list_notification_actions")
+        .then(setNotifications)
+        .catch(console.error);
+    }
+
+    load();
+    const changed = listen("notification-actions-changed", load);
+
+    return () => {
+      changed.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  if (notifications.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="mb-6 space-y-3">
+      <p className="text-sm font-semibold text-foreground">Notifications</p>
+      {notifications.map((notification) => (
+        <ActionCard key={notification.id} notification={notification} />
+      ))}
+    </div>
+  );
+}
+
+function ActionCard({
+  notification,
+}: {
+  notification: ActionableNotification;
+}) {
+  const [replyTo, setReplyTo] = useState<string | null>(null);
+  const [text, setText] = useState("");
+  const [sending, setSending] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function run(actionId: string | null, reply: string | null = null) {
+    setSending(true);
+    setError(null);
+    try {
+      await invoke("run_notification_action", {
+        id: notification.id,
+        actionId,
+        text: reply,
+      });
+      setReplyTo(null);
+      setText("");
+    } catch (err) {
+      console.error(err);
+      setError(String(err));
+    } finally {
+      setSending(false);
+    }
+  }
+
+  async function press(action: NotificationAction) {
+    if (action.type === "reply" && action.allow_text) {
+      setReplyTo(action.id);
+      return;
+    }
+    if (action.type === "copy") {
+      try {
+        await navigator.clipboard.writeText(action.text);
+      } catch (err) {
+        console.error(err);
+        setError("Failed to copy to the clipboard");
+        return;
+      }
+    }
+    await run(action.id);
+  }
+
+  async function dismiss() {
+    try {
+      await invoke("dismiss_notification_actions", { id: notification.id });
+    } catch (err) {
+      console.error(err);
+      setError(String(err));
+    }
+  }
+
+  const last = notification.results[notification.results.length - 1];
+
+  return (
+    <div className="space-y-3 rounded-2xl border border-border bg-card px-4 py-4">
+      <div className="flex items-start gap-3">
+        <div className="flex h-9 w-9 shrink-0 items-center justify-center rounded-full bg-muted text-foreground">
+          <Bell className="h-4 w-4" />
+        </div>
+        <div className="flex-1 space-y-1">
+          <p className="text-xs text-muted-foreground">
+            {notification.agent} at{" "}
+            {new Date(notification.created_at).toLocaleTimeString()}
+          </p>
+          {notification.url ? (
+            <button
+              type="button"
+              disabled={sending}
+              onClick={() => run(null)}
+              className="flex items-center gap-1 text-left text-sm font-semibold text-foreground hover:underline"
+            >
+              {notification.title}
+              <ExternalLink className="h-3 w-3 text-muted-foreground" />
+            </button>
+          ) : (
+            <p className="text-sm font-semibold text-foreground">
+              {notification.title}
+            </p>
+          )}
+          <p className="whitespace-pre-wrap text-sm text-foreground">
+            {notification.content}
+          </p>
+        </div>
+        <Button
+          variant="ghost"
+          size="icon"
+          aria-label="Dismiss"
+          onClick={dismiss}
+        >
+          <X className="h-4 w-4" />
+        </Button>
+      </div>
+
+      {notification.actions.length > 0 && (
+        <div className="flex flex-wrap gap-2">
+          {notification.actions.map((action) => (
+            <Button
+              key={action.id}
+              variant="secondary"
+              disabled={sending}
+              onClick={() => press(action)}
+              title={action.type === "open_path" ? action.path : undefined}
+            >
+              {action.label}
+              {action.type === "open_path" && (
+                <span className="max-w-[240px] truncate text-xs text-muted-foreground">
+                  {action.path}
+                </span>
+              )}
+            </Button>
+          ))}
+        </div>
+      )}
+
+      {replyTo && (
+        <form
+          className="flex gap-3"
+          onSubmit={(e) => {
+            e.preventDefault();
+            run(replyTo, text);
+          }}
+        >
+          <Input
+            autoFocus
+            value={text}
+            onChange={(e) => setText(e.currentTarget.value)}
+            placeholder="Type a reply"
+            className="flex-1 bg-muted/40 text-foreground placeholder:text-muted-foreground"
+          />
+          <Button type="submit" disabled={sending}>
+            {sending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            Send
+          </Button>
+        </form>
+      )}
+
+      {last && (
+        <p className="text-xs text-muted-foreground">
+          Sent “{last.label}” at {new Date(last.at).toLocaleTimeString()}
+        </p>
+      )}
+      {error && <p className="text-sm text-destructive-foreground">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,141 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+
+type CoalesceSettings = {
+  dedupe_window_seconds: number;
+  burst_window_seconds: number;
+  burst_threshold: number;
+};
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+const FIELDS: {
+  key: keyof CoalesceSettings;
+  label: string;
+  hint: string;
+  min: number;
+}[] = [
+  {
+    key: "dedupe_window_seconds",
+    label: "Ignore repeats for (seconds)",
+    hint: "Same agent, title and content. 0 shows every repeat.",
+    min: 0,
+  },
+  {
+    key: "burst_window_seconds",
+    label: "Burst window (seconds)",
+    hint: "Notifications from one agent this close together form a burst. 0 turns this off.",
+    min: 0,
+  },
+  {
+    key: "burst_threshold",
+    label: "Summarize from",
+    hint: "The notification that reaches this count in a burst, and the rest after it, become one summary.",
+    min: 2,
+  },
+];
+
+export function CoalesceSettingsForm() {
+  const [settings, setSettings] = useState<CoalesceSettings | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      setSettings(await invoke<CoalesceSettings>("get_coalesce_settings"));
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load settings" });
+    }
+  }
+
+  async function save() {
+    if (!settings) return;
+    setSaving(true);
+    setStatus(null);
+    try {
+      await invoke("save_coalesce_settings", { settings });
+      setStatus({ type: "success", message: "Settings saved" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  if (!settings) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading settings…
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-5">
+      <div className="divide-y divide-border overflow-hidden rounded-2xl border border-border bg-card">
+        {FIELDS.map(({ key, label, hint, min }) => (
+          <div
+            key={key}
+            className="flex flex-wrap items-center justify-between gap-4 px-4 py-4"
+          >
+            <div className="space-y-1">
+              <Label htmlFor={key} className="text-foreground">
+                {label}
+              </Label>
+              <p className="text-xs text-muted-foreground">{hint}</p>
+            </div>
+            <Input
+              id={key}
+              type="number"
+              min={min}
+              value={settings[key]}
+              onChange={(e) => {
+                setSettings({
+                  ...settings,
+                  [key]: Number(e.currentTarget.value),
+                });
+                setStatus(null);
+              }}
+              className="w-24 bg-muted/40 text-foreground"
+            />
+          </div>
+        ))}
+      </div>
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={save} disabled={saving}>
+          {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,91 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { BellRing, Check } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+
+type Escalation = {
+  id: string;
+  agent: string;
+  title: string;
+  content: string;
+  level: string;
+  delivered_at: number;
+  reminders_sent: number;
+  next_at: number;
+  policy: { name: string; reminders: number };
+};
+
+/** Notifications an escalation policy is waiting for the user to acknowledge. */
+export function EscalationPanel() {
+  const [escalations, setEscalations] = useState<Escalation[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    function load() {
+      invoke<Escalation[]>("list_escalations")
+        .then(setEscalations)
+        .catch(console.error);
+    }
+
+    load();
+    const changed = listen("escalations-changed", load);
+
+    return () => {
+      changed.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  async function acknowledge(id: string) {
+    setError(null);
+    try {
+      await invoke("acknowledge_notification", { id });
+    } catch (err) {
+      console.error(err);
+      setError(String(err));
+    }
+  }
+
+  if (escalations.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="mb-6 space-y-3">
+      <p className="text-sm font-semibold text-foreground">
+        Waiting for acknowledgement
+      </p>
+      {escalations.map((escalation) => (
+        <div
+          key={escalation.id}
+          className="flex items-start gap-3 rounded-2xl border border-destructive/60 bg-destructive/10 px-4 py-4"
+        >
+          <div className="flex h-9 w-9 shrink-0 items-center justify-center rounded-full bg-muted text-foreground">
+            <BellRing className="h-4 w-4" />
+          </div>
+          <div className="flex-1 space-y-1">
+            <p className="text-xs text-muted-foreground">
+              {escalation.agent} at{" "}
+              {new Date(escalation.delivered_at).toLocaleTimeString()} ·{" "}
+              {escalation.reminders_sent < escalation.policy.reminders
+                ? `next reminder at ${new Date(escalation.next_at).toLocaleTimeString()}`
+                : `escalates at ${new Date(escalation.next_at).toLocaleTimeString()}`}
+            </p>
+            <p className="text-sm font-semibold text-foreground">
+              {escalation.title}
+            </p>
+            <p className="whitespace-pre-wrap text-sm text-foreground">
+              {escalation.content}
+            </p>
+          </div>
+          <Button onClick={() => acknowledge(escalation.id)}>
+            <Check className="h-4 w-4" />
+            Acknowledge
+          </Button>
+        </div>
+      ))}
+      {error && <p className="text-sm text-destructive-foreground">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,305 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2, Plus, Trash2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { SoundPicker, selectClassName } from "@/components/sound-picker";
+
+const LEVELS = ["info", "success", "warning", "error", "critical"] as const;
+
+type Level = (typeof LEVELS)[number];
+
+type Sink =
+  | { type: "webhook"; url: string; headers?: Record<string, string> }
+  | { type: "exec"; command: string; args: string[] };
+
+type Policy = {
+  name: string;
+  enabled: boolean;
+  agent: string | null;
+  levels: Level[];
+  interval_seconds: number;
+  reminders: number;
+  sound: string | null;
+  sink: Sink | null;
+};
+
+type EscalationSettings = { policies: Policy[] };
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+const inputClassName =
+  "bg-muted/40 text-foreground placeholder:text-muted-foreground";
+
+function newPolicy(): Policy {
+  return {
+    name: "",
+    enabled: true,
+    agent: null,
+    levels: ["critical"],
+    interval_seconds: 300,
+    reminders: 3,
+    sound: null,
+    sink: null,
+  };
+}
+
+export function EscalationSettingsForm() {
+  const [policies, setPolicies] = useState<Policy[] | null>(null);
+  const [sounds, setSounds] = useState<string[]>([]);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      const [settings, bundled] = await Promise.all([
+        invoke<EscalationSettings>("get_escalation_settings"),
+        invoke<string[]>("list_bundled_sounds"),
+      ]);
+      setPolicies(settings.policies);
+      setSounds(bundled);
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load escalation policies" });
+    }
+  }
+
+  function updatePolicy(index: number, patch: Partial<Policy>) {
+    setPolicies((prev) =>
+      prev
+        ? prev.map((policy, i) => (i === index ? { ...policy, ...patch } : policy))
+        : prev,
+    );
+    setStatus(null);
+  }
+
+  function toggleLevel(index: number, level: Level) {
+    if (!policies) return;
+    const levels = policies[index].levels;
+    updatePolicy(index, {
+      levels: levels.includes(level)
+        ? levels.filter((existing) => existing !== level)
+        : [...levels, level],
+    });
+  }
+
+  async function save() {
+    if (!policies) return;
+    setSaving(true);
+    setStatus(null);
+    try {
+      const saved = await invoke<EscalationSettings>("save_escalation_settings", {
+        settings: { policies },
+      });
+      setPolicies(saved.policies);
+      setStatus({ type: "success", message: "Escalation policies saved" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  if (!policies) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading escalation policies…
+      </div>
+    );
+  }
+
+  function renderSink(index: number, sink: Sink | null) {
+    const update = (next: Sink | null) => updatePolicy(index, { sink: next });
+    return (
+      <div className="flex flex-1 flex-wrap items-center gap-2">
+        <select
+          aria-label="Then"
+          value={sink?.type ?? ""}
+          onChange={(e) => {
+            const type = e.currentTarget.value;
+            update(
+              type === "webhook"
+                ? { type: "webhook", url: "" }
+                : type === "exec"
+                  ? { type: "exec", command: "", args: [] }
+                  : null,
+            );
+          }}
+          className={selectClassName}
+        >
+          <option value="">Then stop</option>
+          <option value="webhook">Then call a webhook</option>
+          <option value="exec">Then run a command</option>
+        </select>
+        {sink?.type === "webhook" && (
+          <Input
+            aria-label="Webhook URL"
+            value={sink.url}
+            onChange={(e) => update({ ...sink, url: e.currentTarget.value })}
+            placeholder="https://example.com/hook"
+            className={`min-w-[180px] flex-1 ${inputClassName}`}
+          />
+        )}
+        {sink?.type === "exec" && (
+          <Input
+            aria-label="Command"
+            value={[sink.command, ...sink.args].join(" ")}
+            onChange={(e) => {
+              const [command = "", ...args] = e.currentTarget.value.split(" ");
+              update({ type: "exec", command, args });
+            }}
+            placeholder="/usr/local/bin/page-me --team infra"
+            className={`min-w-[180px] flex-1 ${inputClassName}`}
+          />
+        )}
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-5">
+      {policies.length === 0 && (
+        <p className="text-sm text-muted-foreground">
+          No policies yet; notifications are shown once and never escalated.
+        </p>
+      )}
+
+      {policies.map((policy, index) => (
+        <div
+          key={index}
+          className="space-y-3 rounded-2xl border border-border bg-card px-4 py-4"
+        >
+          <div className="flex items-center gap-3">
+            <input
+              type="checkbox"
+              aria-label="Policy enabled"
+              checked={policy.enabled}
+              onChange={(e) =>
+                updatePolicy(index, { enabled: e.currentTarget.checked })
+              }
+              className="h-4 w-4 accent-current"
+            />
+            <Input
+              aria-label="Policy name"
+              value={policy.name}
+              onChange={(e) => updatePolicy(index, { name: e.currentTarget.value })}
+              placeholder="Policy name"
+              className={`min-w-[180px] flex-1 ${inputClassName}`}
+            />
+            <Button
+              variant="ghost"
+              size="icon"
+              aria-label="Remove policy"
+              onClick={() => {
+                setPolicies(policies.filter((_, i) => i !== index));
+                setStatus(null);
+              }}
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+
+          <div className="flex flex-wrap items-center gap-3 text-sm text-muted-foreground">
+            {LEVELS.map((level) => (
+              <label key={level} className="flex items-center gap-1 capitalize">
+                <input
+                  type="checkbox"
+                  checked={policy.levels.includes(level)}
+                  onChange={() => toggleLevel(index, level)}
+                  className="h-4 w-4 accent-current"
+                />
+                {level}
+              </label>
+            ))}
+            <Input
+              aria-label="Agent"
+              value={policy.agent ?? ""}
+              onChange={(e) =>
+                updatePolicy(index, { agent: e.currentTarget.value || null })
+              }
+              placeholder="Any agent"
+              className={`w-36 ${inputClassName}`}
+            />
+          </div>
+
+          <div className="flex flex-wrap items-center gap-2 text-sm text-muted-foreground">
+            Remind
+            <Input
+              type="number"
+              min={0}
+              max={10}
+              aria-label="Reminders"
+              value={policy.reminders}
+              onChange={(e) =>
+                updatePolicy(index, { reminders: Number(e.currentTarget.value) })
+              }
+              className="w-20 bg-muted/40 text-foreground"
+            />
+            times, every
+            <Input
+              type="number"
+              min={30}
+              aria-label="Interval in seconds"
+              value={policy.interval_seconds}
+              onChange={(e) =>
+                updatePolicy(index, {
+                  interval_seconds: Number(e.currentTarget.value),
+                })
+              }
+              className="w-24 bg-muted/40 text-foreground"
+            />
+            seconds, with
+            <SoundPicker
+              label="Reminder sound"
+              value={policy.sound}
+              bundled={sounds}
+              emptyLabel="The usual sound"
+              onChange={(sound) => updatePolicy(index, { sound })}
+              onError={(message) => setStatus({ type: "error", message })}
+            />
+          </div>
+
+          {renderSink(index, policy.sink)}
+        </div>
+      ))}
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={save} disabled={saving}>
+          {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save
+        </Button>
+        <Button
+          variant="secondary"
+          onClick={() => setPolicies([...policies, newPolicy()])}
+          disabled={saving}
+        >
+          <Plus className="h-4 w-4" />
+          Add policy
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
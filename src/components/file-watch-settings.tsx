@@ -0,0 +1,254 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2, Plus, Trash2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { selectClassName } from "@/components/sound-picker";
+
+const LEVELS = ["info", "success", "warning", "error", "critical"] as const;
+
+type Level = (typeof LEVELS)[number];
+
+type WatchKind =
+  | { type: "appears" }
+  | { type: "changes" }
+  | { type: "matches"; pattern: string };
+
+type FileWatch = WatchKind & {
+  id: string;
+  path: string;
+  agent: string;
+  title: string | null;
+  level: Level;
+  once: boolean;
+  created_at: number;
+};
+
+type Draft = {
+  path: string;
+  type: WatchKind["type"];
+  pattern: string;
+  title: string;
+  level: Level;
+  once: boolean;
+};
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+const inputClassName =
+  "bg-muted/40 text-foreground placeholder:text-muted-foreground";
+
+const emptyDraft: Draft = {
+  path: "",
+  type: "appears",
+  pattern: "",
+  title: "",
+  level: "info",
+  once: true,
+};
+
+function describe(watch: FileWatch) {
+  switch (watch.type) {
+    case "appears":
+      return "When it appears";
+    case "changes":
+      return "When it changes";
+    case "matches":
+      return `When a new line matches /${watch.pattern}/`;
+  }
+}
+
+export function FileWatchesForm() {
+  const [watches, setWatches] = useState<FileWatch[] | null>(null);
+  const [draft, setDraft] = useState<Draft>(emptyDraft);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      setWatches(await invoke<FileWatch[]>("list_file_watches"));
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load watches" });
+    }
+  }
+
+  function updateDraft(patch: Partial<Draft>) {
+    setDraft((prev) => ({ ...prev, ...patch }));
+    setStatus(null);
+  }
+
+  async function add() {
+    setSaving(true);
+    setStatus(null);
+    try {
+      await invoke("add_file_watch", {
+        watch: {
+          path: draft.path,
+          type: draft.type,
+          ...(draft.type === "matches" ? { pattern: draft.pattern } : {}),
+          title: draft.title || null,
+          level: draft.level,
+          once: draft.once,
+        },
+      });
+      setDraft(emptyDraft);
+      setWatches(await invoke<FileWatch[]>("list_file_watches"));
+      setStatus({ type: "success", message: "Watch added" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function remove(id: string) {
+    setStatus(null);
+    try {
+      await invoke("remove_file_watch", { id });
+      setWatches((prev) => prev?.filter((watch) => watch.id !== id) ?? prev);
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    }
+  }
+
+  if (!watches) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading watches…
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      {watches.length === 0 ? (
+        <p className="text-sm text-muted-foreground">No files are being watched.</p>
+      ) : (
+        watches.map((watch) => (
+          <div
+            key={watch.id}
+            className="flex items-start gap-3 rounded-2xl border border-border bg-card px-4 py-3"
+          >
+            <div className="flex-1 space-y-1">
+              <p className="break-all font-mono text-sm text-foreground">
+                {watch.path}
+              </p>
+              <p className="text-xs text-muted-foreground">
+                {describe(watch)} · {watch.agent} · {watch.level}
+                {watch.once ? " · once" : ""}
+              </p>
+            </div>
+            <Button
+              variant="ghost"
+              size="icon"
+              aria-label="Remove watch"
+              onClick={() => remove(watch.id)}
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+        ))
+      )}
+
+      <div className="space-y-3 rounded-2xl border border-border bg-card px-4 py-4">
+        <p className="text-sm font-semibold text-foreground">Add a watch</p>
+        <Input
+          aria-label="Path"
+          value={draft.path}
+          onChange={(e) => updateDraft({ path: e.currentTarget.value })}
+          placeholder="/absolute/path/to/file.log"
+          className={inputClassName}
+        />
+        <div className="flex flex-wrap items-center gap-3">
+          <select
+            aria-label="Watch type"
+            value={draft.type}
+            onChange={(e) =>
+              updateDraft({ type: e.currentTarget.value as Draft["type"] })
+            }
+            className={selectClassName}
+          >
+            <option value="appears">Appears</option>
+            <option value="changes">Changes</option>
+            <option value="matches">Line matches</option>
+          </select>
+          {draft.type === "matches" && (
+            <Input
+              aria-label="Pattern"
+              value={draft.pattern}
+              onChange={(e) => updateDraft({ pattern: e.currentTarget.value })}
+              placeholder="Regular expression, e.g. ERROR|panicked"
+              className={`min-w-[180px] flex-1 ${inputClassName}`}
+            />
+          )}
+        </div>
+        <div className="flex flex-wrap items-center gap-3">
+          <Input
+            aria-label="Title"
+            value={draft.title}
+            onChange={(e) => updateDraft({ title: e.currentTarget.value })}
+            placeholder="Notification title (optional)"
+            className={`min-w-[180px] flex-1 ${inputClassName}`}
+          />
+          <select
+            aria-label="Level"
+            value={draft.level}
+            onChange={(e) => updateDraft({ level: e.currentTarget.value as Level })}
+            className={selectClassName}
+          >
+            {LEVELS.map((level) => (
+              <option key={level} value={level}>
+                {level}
+              </option>
+            ))}
+          </select>
+          <label className="flex items-center gap-2 text-sm text-foreground">
+            <input
+              type="checkbox"
+              checked={draft.once}
+              onChange={(e) => updateDraft({ once: e.currentTarget.checked })}
+              className="h-4 w-4 accent-current"
+            />
+            Only once
+          </label>
+        </div>
+      </div>
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={add} disabled={saving || !draft.path.trim()}>
+          {saving ? (
+            <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+          ) : (
+            <Plus className="h-4 w-4" />
+          )}
+          Add watch
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,152 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { SoundPicker, selectClassName } from "@/components/sound-picker";
+
+const LEVELS = ["info", "success", "warning", "error", "critical"] as const;
+
+type Level = (typeof LEVELS)[number];
+
+type Urgency = "low" | "normal" | "critical";
+
+type LevelStyle = {
+  title_prefix: string;
+  icon: string | null;
+  sound: string | null;
+  urgency: Urgency;
+};
+
+type LevelSettings = Record<Level, LevelStyle>;
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+export function LevelSettingsForm() {
+  const [settings, setSettings] = useState<LevelSettings | null>(null);
+  const [sounds, setSounds] = useState<string[]>([]);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      const [levels, bundled] = await Promise.all([
+        invoke<LevelSettings>("get_level_settings"),
+        invoke<string[]>("list_bundled_sounds"),
+      ]);
+      setSettings(levels);
+      setSounds(bundled);
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load level settings" });
+    }
+  }
+
+  function update(level: Level, patch: Partial<LevelStyle>) {
+    setSettings((prev) =>
+      prev ? { ...prev, [level]: { ...prev[level], ...patch } } : prev,
+    );
+    setStatus(null);
+  }
+
+  async function save() {
+    if (!settings) return;
+    setSaving(true);
+    setStatus(null);
+    try {
+      await invoke("save_level_settings", { settings });
+      setStatus({ type: "success", message: "Notification levels saved" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  if (!settings) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading levels…
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-5">
+      <div className="divide-y divide-border overflow-hidden rounded-2xl border border-border bg-card">
+        {LEVELS.map((level) => (
+          <div
+            key={level}
+            className="flex flex-wrap items-center justify-between gap-4 px-4 py-4"
+          >
+            <Label className="w-20 capitalize text-foreground">{level}</Label>
+            <div className="flex flex-1 flex-wrap items-center gap-3">
+              <Input
+                aria-label={`${level} title prefix`}
+                value={settings[level].title_prefix}
+                onChange={(e) =>
+                  update(level, { title_prefix: e.currentTarget.value })
+                }
+                placeholder="Title prefix"
+                className="w-28 bg-muted/40 text-foreground placeholder:text-muted-foreground"
+              />
+              <SoundPicker
+                label={`${level} sound`}
+                value={settings[level].sound}
+                bundled={sounds}
+                emptyLabel="Default sound"
+                onChange={(sound) => update(level, { sound })}
+                onError={(message) => setStatus({ type: "error", message })}
+              />
+              <select
+                aria-label={`${level} urgency`}
+                value={settings[level].urgency}
+                onChange={(e) =>
+                  update(level, {
+                    urgency: e.currentTarget.value as Urgency,
+                  })
+                }
+                className={selectClassName}
+              >
+                <option value="low">Low urgency (no sound)</option>
+                <option value="normal">Normal urgency</option>
+                <option value="critical">Critical urgency</option>
+              </select>
+            </div>
+          </div>
+        ))}
+      </div>
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={save} disabled={saving}>
+          {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,135 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Loader2, MessageSquare } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+
+type PendingPrompt = {
+  id: string;
+  agent: string;
+  question: string;
+  choices: string[];
+  allow_text: boolean;
+  created_at: number;
+  expires_at: number;
+};
+
+type PromptAnswer = { choice: string | null; text: string | null };
+
+export function PromptPanel() {
+  const [prompts, setPrompts] = useState<PendingPrompt[]>([]);
+
+  useEffect(() => {
+    invoke<PendingPrompt[]>("list_pending_prompts")
+      .then(setPrompts)
+      .catch(console.error);
+
+    const opened = listen<PendingPrompt>("prompt-opened", (event) => {
+      setPrompts((prev) => [
+        ...prev.filter((prompt) => prompt.id !== event.payload.id),
+        event.payload,
+      ]);
+    });
+    const closed = listen<string>("prompt-closed", (event) => {
+      setPrompts((prev) =>
+        prev.filter((prompt) => prompt.id !== event.payload),
+      );
+    });
+
+    return () => {
+      opened.then((unlisten) => unlisten());
+      closed.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  if (prompts.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="mb-6 space-y-3">
+      <p className="text-sm font-semibold text-foreground">Agent questions</p>
+      {prompts.map((prompt) => (
+        <PromptCard key={prompt.id} prompt={prompt} />
+      ))}
+    </div>
+  );
+}
+
+function PromptCard({ prompt }: { prompt: PendingPrompt }) {
+  const [text, setText] = useState("");
+  const [sending, setSending] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  async function answer(value: PromptAnswer) {
+    setSending(true);
+    setError(null);
+    try {
+      await invoke("answer_prompt", { id: prompt.id, answer: value });
+    } catch (err) {
+      console.error(err);
+      setError(String(err));
+    } finally {
+      setSending(false);
+    }
+  }
+
+  return (
+    <div className="space-y-3 rounded-2xl border border-border bg-card px-4 py-4">
+      <div className="flex items-start gap-3">
+        <div className="flex h-9 w-9 shrink-0 items-center justify-center rounded-full bg-muted text-foreground">
+          <MessageSquare className="h-4 w-4" />
+        </div>
+        <div className="space-y-1">
+          <p className="text-xs text-muted-foreground">
+            {prompt.agent} is waiting until{" "}
+            {new Date(prompt.expires_at).toLocaleTimeString()}
+          </p>
+          <p className="whitespace-pre-wrap text-sm text-foreground">
+            {prompt.question}
+          </p>
+        </div>
+      </div>
+
+      {prompt.choices.length > 0 && (
+        <div className="flex flex-wrap gap-2">
+          {prompt.choices.map((choice) => (
+            <Button
+              key={choice}
+              variant="secondary"
+              disabled={sending}
+              onClick={() => answer({ choice, text: null })}
+            >
+              {choice}
+            </Button>
+          ))}
+        </div>
+      )}
+
+      {prompt.allow_text && (
+        <form
+          className="flex gap-3"
+          onSubmit={(e) => {
+            e.preventDefault();
+            answer({ choice: null, text });
+          }}
+        >
+          <Input
+            value={text}
+            onChange={(e) => setText(e.currentTarget.value)}
+            placeholder="Type an answer"
+            className="flex-1 bg-muted/40 text-foreground placeholder:text-muted-foreground"
+          />
+          <Button type="submit" disabled={sending || !text.trim()}>
+            {sending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            Send
+          </Button>
+        </form>
+      )}
+
+      {error && <p className="text-sm text-destructive-foreground">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,280 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2, Plus, Trash2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+
+const DAYS = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"] as const;
+
+type Day = (typeof DAYS)[number];
+
+type QuietSchedule = {
+  enabled: boolean;
+  days: Day[];
+  start: string;
+  end: string;
+};
+
+type QuietSettings = { schedules: QuietSchedule[] };
+
+type QuietStatus = {
+  active: boolean;
+  paused_until: number | null;
+  in_schedule: boolean;
+  queued: number;
+};
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+function describe(quiet: QuietStatus) {
+  if (quiet.paused_until) {
+    const until = new Date(quiet.paused_until).toLocaleTimeString([], {
+      hour: "2-digit",
+      minute: "2-digit",
+    });
+    return `Paused until ${until}`;
+  }
+  if (quiet.in_schedule) return "In quiet hours";
+  return "Notifications are on";
+}
+
+export function QuietSettingsForm() {
+  const [settings, setSettings] = useState<QuietSettings | null>(null);
+  const [quiet, setQuiet] = useState<QuietStatus | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+    // Pauses run out and schedules start on their own, so keep the summary fresh.
+    const timer = window.setInterval(refreshStatus, 30_000);
+    return () => window.clearInterval(timer);
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      const [loaded, current] = await Promise.all([
+        invoke<QuietSettings>("get_quiet_settings"),
+        invoke<QuietStatus>("get_quiet_status"),
+      ]);
+      setSettings(loaded);
+      setQuiet(current);
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load Do Not Disturb" });
+    }
+  }
+
+  async function refreshStatus() {
+    try {
+      setQuiet(await invoke<QuietStatus>("get_quiet_status"));
+    } catch (err) {
+      console.error(err);
+    }
+  }
+
+  async function pause(minutes: number) {
+    try {
+      setQuiet(await invoke<QuietStatus>("pause_notifications", { minutes }));
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    }
+  }
+
+  async function resume() {
+    try {
+      setQuiet(await invoke<QuietStatus>("resume_notifications"));
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    }
+  }
+
+  function update(index: number, patch: Partial<QuietSchedule>) {
+    setSettings((prev) =>
+      prev
+        ? {
+            schedules: prev.schedules.map((schedule, i) =>
+              i === index ? { ...schedule, ...patch } : schedule,
+            ),
+          }
+        : prev,
+    );
+    setStatus(null);
+  }
+
+  function toggleDay(index: number, day: Day) {
+    if (!settings) return;
+    const days = settings.schedules[index].days;
+    update(index, {
+      days: days.includes(day)
+        ? days.filter((d) => d !== day)
+        : DAYS.filter((d) => d === day || days.includes(d)),
+    });
+  }
+
+  async function save() {
+    if (!settings) return;
+    setSaving(true);
+    setStatus(null);
+    try {
+      await invoke("save_quiet_settings", { settings });
+      setStatus({ type: "success", message: "Quiet hours saved" });
+      await refreshStatus();
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  if (!settings || !quiet) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading Do Not Disturb…
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-5">
+      <div className="flex flex-wrap items-center justify-between gap-4 rounded-2xl border border-border bg-card px-4 py-4">
+        <div className="space-y-1">
+          <p className="text-sm font-medium text-foreground">
+            {describe(quiet)}
+          </p>
+          <p className="text-xs text-muted-foreground">
+            {quiet.queued === 1
+              ? "1 notification waiting"
+              : `${quiet.queued} notifications waiting`}
+          </p>
+        </div>
+        <div className="flex flex-wrap gap-2">
+          <Button variant="secondary" onClick={() => pause(30)}>
+            30 minutes
+          </Button>
+          <Button variant="secondary" onClick={() => pause(60)}>
+            1 hour
+          </Button>
+          <Button variant="ghost" onClick={resume} disabled={!quiet.paused_until}>
+            Resume
+          </Button>
+        </div>
+      </div>
+
+      {settings.schedules.length > 0 && (
+        <div className="divide-y divide-border overflow-hidden rounded-2xl border border-border bg-card">
+          {settings.schedules.map((schedule, index) => (
+            <div key={index} className="space-y-3 px-4 py-4">
+              <div className="flex flex-wrap items-center gap-3">
+                <input
+                  type="checkbox"
+                  aria-label="Schedule enabled"
+                  checked={schedule.enabled}
+                  onChange={(e) =>
+                    update(index, { enabled: e.currentTarget.checked })
+                  }
+                  className="h-4 w-4 accent-current"
+                />
+                <Input
+                  type="time"
+                  aria-label="Start"
+                  value={schedule.start}
+                  onChange={(e) =>
+                    update(index, { start: e.currentTarget.value })
+                  }
+                  className="w-32 bg-muted/40 text-foreground"
+                />
+                <span className="text-sm text-muted-foreground">to</span>
+                <Input
+                  type="time"
+                  aria-label="End"
+                  value={schedule.end}
+                  onChange={(e) => update(index, { end: e.currentTarget.value })}
+                  className="w-32 bg-muted/40 text-foreground"
+                />
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  aria-label="Remove schedule"
+                  className="ml-auto"
+                  onClick={() => {
+                    setSettings({
+                      schedules: settings.schedules.filter((_, i) => i !== index),
+                    });
+                    setStatus(null);
+                  }}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </div>
+              <div className="flex flex-wrap gap-2">
+                {DAYS.map((day) => (
+                  <label
+                    key={day}
+                    className="flex cursor-pointer items-center gap-1 text-xs capitalize text-muted-foreground"
+                  >
+                    <input
+                      type="checkbox"
+                      checked={schedule.days.includes(day)}
+                      onChange={() => toggleDay(index, day)}
+                      className="h-3 w-3 accent-current"
+                    />
+                    {day}
+                  </label>
+                ))}
+                {schedule.days.length === 0 && (
+                  <span className="text-xs text-muted-foreground">
+                    (every day)
+                  </span>
+                )}
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={save} disabled={saving}>
+          {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save
+        </Button>
+        <Button
+          variant="secondary"
+          onClick={() =>
+            setSettings({
+              schedules: [
+                ...settings.schedules,
+                { enabled: true, days: [], start: "22:00", end: "07:00" },
+              ],
+            })
+          }
+          disabled={saving}
+        >
+          <Plus className="h-4 w-4" />
+          Add quiet hours
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,137 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+
+type RateLimit = { per_minute: number; burst: number };
+
+type RateLimitSettings = {
+  per_agent: RateLimit;
+  per_client: RateLimit;
+  global: RateLimit;
+};
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+const SCOPES: { key: keyof RateLimitSettings; label: string; hint: string }[] = [
+  { key: "per_agent", label: "Per agent", hint: "Each agent label." },
+  { key: "per_client", label: "Per client", hint: "Each IP address calling the server." },
+  { key: "global", label: "Overall", hint: "All notifications together." },
+];
+
+export function RateLimitSettingsForm() {
+  const [settings, setSettings] = useState<RateLimitSettings | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      setSettings(await invoke<RateLimitSettings>("get_rate_limit_settings"));
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load rate limits" });
+    }
+  }
+
+  function update(key: keyof RateLimitSettings, patch: Partial<RateLimit>) {
+    setSettings((prev) =>
+      prev ? { ...prev, [key]: { ...prev[key], ...patch } } : prev,
+    );
+    setStatus(null);
+  }
+
+  async function save() {
+    if (!settings) return;
+    setSaving(true);
+    setStatus(null);
+    try {
+      await invoke("save_rate_limit_settings", { settings });
+      setStatus({ type: "success", message: "Rate limits saved" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  if (!settings) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading rate limits…
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-5">
+      <div className="divide-y divide-border overflow-hidden rounded-2xl border border-border bg-card">
+        {SCOPES.map(({ key, label, hint }) => (
+          <div
+            key={key}
+            className="flex flex-wrap items-center justify-between gap-4 px-4 py-4"
+          >
+            <div className="space-y-1">
+              <Label className="text-foreground">{label}</Label>
+              <p className="text-xs text-muted-foreground">{hint}</p>
+            </div>
+            <div className="flex items-center gap-2 text-sm text-muted-foreground">
+              <Input
+                type="number"
+                min={0}
+                aria-label={`${label} per minute`}
+                value={settings[key].per_minute}
+                onChange={(e) =>
+                  update(key, { per_minute: Number(e.currentTarget.value) })
+                }
+                className="w-20 bg-muted/40 text-foreground"
+              />
+              per minute, bursts of
+              <Input
+                type="number"
+                min={1}
+                aria-label={`${label} burst`}
+                value={settings[key].burst}
+                onChange={(e) =>
+                  update(key, { burst: Number(e.currentTarget.value) })
+                }
+                className="w-20 bg-muted/40 text-foreground"
+              />
+            </div>
+          </div>
+        ))}
+      </div>
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={save} disabled={saving}>
+          {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,475 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { ArrowDown, ArrowUp, Loader2, Plus, Trash2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { SoundPicker, selectClassName } from "@/components/sound-picker";
+
+const LEVELS = ["info", "success", "warning", "error", "critical"] as const;
+
+type Level = (typeof LEVELS)[number];
+
+type Source = "http" | "mcp";
+
+type Sink =
+  | { type: "webhook"; url: string; headers?: Record<string, string> }
+  | { type: "exec"; command: string; args: string[] };
+
+type RuleAction =
+  | { type: "mute" }
+  | { type: "set_sound"; sound: string }
+  | { type: "rewrite_title"; title: string }
+  | { type: "forward"; sink: Sink }
+  | { type: "escalate"; level: Level };
+
+type Rule = {
+  id: string;
+  name: string;
+  enabled: boolean;
+  conditions: {
+    agent: string | null;
+    title: string | null;
+    content: string | null;
+    levels: Level[];
+    sources: Source[];
+  };
+  actions: RuleAction[];
+};
+
+type RuleSettings = { rules: Rule[] };
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+const inputClassName =
+  "bg-muted/40 text-foreground placeholder:text-muted-foreground";
+
+function newAction(type: RuleAction["type"]): RuleAction {
+  switch (type) {
+    case "mute":
+      return { type };
+    case "set_sound":
+      return { type, sound: "Ping" };
+    case "rewrite_title":
+      return { type, title: "{title}" };
+    case "forward":
+      return { type, sink: { type: "webhook", url: "" } };
+    case "escalate":
+      return { type, level: "critical" };
+  }
+}
+
+function newRule(): Rule {
+  return {
+    id: "",
+    name: "",
+    enabled: true,
+    conditions: {
+      agent: null,
+      title: null,
+      content: null,
+      levels: [],
+      sources: [],
+    },
+    actions: [{ type: "mute" }],
+  };
+}
+
+export function RuleSettingsForm() {
+  const [rules, setRules] = useState<Rule[] | null>(null);
+  const [sounds, setSounds] = useState<string[]>([]);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      const [settings, bundled] = await Promise.all([
+        invoke<RuleSettings>("get_notification_rules"),
+        invoke<string[]>("list_bundled_sounds"),
+      ]);
+      setRules(settings.rules);
+      setSounds(bundled);
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load rules" });
+    }
+  }
+
+  function updateRule(index: number, patch: Partial<Rule>) {
+    setRules((prev) =>
+      prev ? prev.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)) : prev,
+    );
+    setStatus(null);
+  }
+
+  function updateConditions(index: number, patch: Partial<Rule["conditions"]>) {
+    if (!rules) return;
+    updateRule(index, { conditions: { ...rules[index].conditions, ...patch } });
+  }
+
+  function updateAction(index: number, actionIndex: number, action: RuleAction) {
+    if (!rules) return;
+    updateRule(index, {
+      actions: rules[index].actions.map((existing, i) =>
+        i === actionIndex ? action : existing,
+      ),
+    });
+  }
+
+  function move(index: number, offset: number) {
+    setRules((prev) => {
+      if (!prev) return prev;
+      const next = [...prev];
+      const [rule] = next.splice(index, 1);
+      next.splice(index + offset, 0, rule);
+      return next;
+    });
+    setStatus(null);
+  }
+
+  async function save() {
+    if (!rules) return;
+    setSaving(true);
+    setStatus(null);
+    try {
+      const saved = await invoke<RuleSettings>("save_notification_rules", {
+        settings: { rules },
+      });
+      setRules(saved.rules);
+      setStatus({ type: "success", message: "Rules saved" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  if (!rules) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading rules…
+      </div>
+    );
+  }
+
+  function renderAction(index: number, actionIndex: number, action: RuleAction) {
+    const update = (next: RuleAction) => updateAction(index, actionIndex, next);
+    switch (action.type) {
+      case "mute":
+        return (
+          <span className="text-xs text-muted-foreground">
+            No toast or sound; still recorded in the history.
+          </span>
+        );
+      case "set_sound":
+        return (
+          <SoundPicker
+            label="Rule sound"
+            value={action.sound}
+            bundled={sounds}
+            emptyLabel="Choose a sound"
+            onChange={(sound) => update({ ...action, sound: sound ?? "" })}
+            onError={(message) => setStatus({ type: "error", message })}
+          />
+        );
+      case "rewrite_title":
+        return (
+          <Input
+            aria-label="New title"
+            value={action.title}
+            onChange={(e) => update({ ...action, title: e.currentTarget.value })}
+            placeholder="[{agent}] {title}"
+            className={`min-w-[180px] flex-1 ${inputClassName}`}
+          />
+        );
+      case "escalate":
+        return (
+          <select
+            aria-label="Escalate to"
+            value={action.level}
+            onChange={(e) =>
+              update({ ...action, level: e.currentTarget.value as Level })
+            }
+            className={selectClassName}
+          >
+            {LEVELS.map((level) => (
+              <option key={level} value={level}>
+                {level}
+              </option>
+            ))}
+          </select>
+        );
+      case "forward": {
+        const sink = action.sink;
+        return (
+          <div className="flex flex-1 flex-wrap items-center gap-2">
+            <select
+              aria-label="Sink type"
+              value={sink.type}
+              onChange={(e) =>
+                update({
+                  ...action,
+                  sink:
+                    e.currentTarget.value === "exec"
+                      ? { type: "exec", command: "", args: [] }
+                      : { type: "webhook", url: "" },
+                })
+              }
+              className={selectClassName}
+            >
+              <option value="webhook">Webhook</option>
+              <option value="exec">Command</option>
+            </select>
+            {sink.type === "webhook" ? (
+              <Input
+                aria-label="Webhook URL"
+                value={sink.url}
+                onChange={(e) =>
+                  update({
+                    ...action,
+                    sink: { ...sink, url: e.currentTarget.value },
+                  })
+                }
+                placeholder="https://example.com/hook"
+                className={`min-w-[180px] flex-1 ${inputClassName}`}
+              />
+            ) : (
+              <Input
+                aria-label="Command"
+                value={[sink.command, ...sink.args].join(" ")}
+                onChange={(e) => {
+                  const [command = "", ...args] = e.currentTarget.value.split(" ");
+                  update({ ...action, sink: { type: "exec", command, args } });
+                }}
+                placeholder="/usr/local/bin/page-me --team infra"
+                className={`min-w-[180px] flex-1 ${inputClassName}`}
+              />
+            )}
+          </div>
+        );
+      }
+    }
+  }
+
+  return (
+    <div className="space-y-5">
+      {rules.length === 0 && (
+        <p className="text-sm text-muted-foreground">
+          No rules yet. Every notification is shown as sent.
+        </p>
+      )}
+
+      {rules.map((rule, index) => (
+        <div
+          key={rule.id || `new-${index}`}
+          className="space-y-4 rounded-2xl border border-border bg-card px-4 py-4"
+        >
+          <div className="flex flex-wrap items-center gap-3">
+            <input
+              type="checkbox"
+              aria-label="Rule enabled"
+              checked={rule.enabled}
+              onChange={(e) => updateRule(index, { enabled: e.currentTarget.checked })}
+              className="h-4 w-4 accent-current"
+            />
+            <Input
+              aria-label="Rule name"
+              value={rule.name}
+              onChange={(e) => updateRule(index, { name: e.currentTarget.value })}
+              placeholder="Rule name"
+              className={`min-w-[180px] flex-1 ${inputClassName}`}
+            />
+            <Button
+              variant="ghost"
+              size="icon"
+              aria-label="Move rule up"
+              onClick={() => move(index, -1)}
+              disabled={index === 0}
+            >
+              <ArrowUp className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="icon"
+              aria-label="Move rule down"
+              onClick={() => move(index, 1)}
+              disabled={index === rules.length - 1}
+            >
+              <ArrowDown className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="icon"
+              aria-label="Remove rule"
+              onClick={() => {
+                setRules(rules.filter((_, i) => i !== index));
+                setStatus(null);
+              }}
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+
+          <div className="space-y-2">
+            <p className="text-xs font-medium uppercase text-muted-foreground">
+              When
+            </p>
+            <div className="flex flex-wrap gap-2">
+              <Input
+                aria-label="Agent"
+                value={rule.conditions.agent ?? ""}
+                onChange={(e) =>
+                  updateConditions(index, { agent: e.currentTarget.value || null })
+                }
+                placeholder="Any agent"
+                className={`w-36 ${inputClassName}`}
+              />
+              <Input
+                aria-label="Title pattern"
+                value={rule.conditions.title ?? ""}
+                onChange={(e) =>
+                  updateConditions(index, { title: e.currentTarget.value || null })
+                }
+                placeholder="Title regex"
+                className={`w-40 ${inputClassName}`}
+              />
+              <Input
+                aria-label="Content pattern"
+                value={rule.conditions.content ?? ""}
+                onChange={(e) =>
+                  updateConditions(index, {
+                    content: e.currentTarget.value || null,
+                  })
+                }
+                placeholder="Content regex"
+                className={`w-40 ${inputClassName}`}
+              />
+              <select
+                aria-label="Level"
+                value={rule.conditions.levels[0] ?? ""}
+                onChange={(e) =>
+                  updateConditions(index, {
+                    levels: e.currentTarget.value
+                      ? [e.currentTarget.value as Level]
+                      : [],
+                  })
+                }
+                className={selectClassName}
+              >
+                <option value="">Any level</option>
+                {LEVELS.map((level) => (
+                  <option key={level} value={level}>
+                    {level}
+                  </option>
+                ))}
+              </select>
+              <select
+                aria-label="Source"
+                value={rule.conditions.sources[0] ?? ""}
+                onChange={(e) =>
+                  updateConditions(index, {
+                    sources: e.currentTarget.value
+                      ? [e.currentTarget.value as Source]
+                      : [],
+                  })
+                }
+                className={selectClassName}
+              >
+                <option value="">HTTP or MCP</option>
+                <option value="http">HTTP only</option>
+                <option value="mcp">MCP only</option>
+              </select>
+            </div>
+          </div>
+
+          <div className="space-y-2">
+            <p className="text-xs font-medium uppercase text-muted-foreground">
+              Then
+            </p>
+            {rule.actions.map((action, actionIndex) => (
+              <div key={actionIndex} className="flex flex-wrap items-center gap-2">
+                <select
+                  aria-label="Action"
+                  value={action.type}
+                  onChange={(e) =>
+                    updateAction(
+                      index,
+                      actionIndex,
+                      newAction(e.currentTarget.value as RuleAction["type"]),
+                    )
+                  }
+                  className={selectClassName}
+                >
+                  <option value="mute">Mute</option>
+                  <option value="set_sound">Play sound</option>
+                  <option value="rewrite_title">Rewrite title</option>
+                  <option value="forward">Forward</option>
+                  <option value="escalate">Escalate</option>
+                </select>
+                {renderAction(index, actionIndex, action)}
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  aria-label="Remove action"
+                  onClick={() =>
+                    updateRule(index, {
+                      actions: rule.actions.filter((_, i) => i !== actionIndex),
+                    })
+                  }
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </div>
+            ))}
+            <Button
+              variant="ghost"
+              onClick={() =>
+                updateRule(index, { actions: [...rule.actions, newAction("mute")] })
+              }
+            >
+              <Plus className="h-4 w-4" />
+              Add action
+            </Button>
+          </div>
+        </div>
+      ))}
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={save} disabled={saving}>
+          {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save
+        </Button>
+        <Button
+          variant="secondary"
+          onClick={() => setRules([...rules, newRule()])}
+          disabled={saving}
+        >
+          <Plus className="h-4 w-4" />
+          Add rule
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,120 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2, Trash2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+
+type ScheduledNotification = {
+  id: string;
+  title: string;
+  content: string;
+  agent: string;
+  level: string;
+  deliver_at: number;
+  created_at: number;
+};
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+export function ScheduledNotificationsList() {
+  const [scheduled, setScheduled] = useState<ScheduledNotification[] | null>(
+    null,
+  );
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      setScheduled(
+        await invoke<ScheduledNotification[]>("list_scheduled_notifications"),
+      );
+    } catch (err) {
+      console.error(err);
+      setStatus({
+        type: "error",
+        message: "Failed to load scheduled notifications",
+      });
+    }
+  }
+
+  async function cancel(id: string) {
+    setStatus(null);
+    try {
+      await invoke("remove_scheduled_notification", { id });
+      setScheduled((prev) => prev?.filter((item) => item.id !== id) ?? prev);
+      setStatus({ type: "success", message: "Scheduled notification cancelled" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    }
+  }
+
+  if (!scheduled) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading scheduled notifications…
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      {scheduled.length === 0 ? (
+        <p className="text-sm text-muted-foreground">
+          Nothing is scheduled.
+        </p>
+      ) : (
+        scheduled.map((item) => (
+          <div
+            key={item.id}
+            className="flex items-start gap-3 rounded-2xl border border-border bg-card px-4 py-3"
+          >
+            <div className="flex-1 space-y-1">
+              <p className="text-xs text-muted-foreground">
+                {new Date(item.deliver_at).toLocaleString()} · {item.agent} ·{" "}
+                {item.level}
+              </p>
+              <p className="text-sm font-semibold text-foreground">
+                {item.title}
+              </p>
+              <p className="whitespace-pre-wrap text-sm text-foreground">
+                {item.content}
+              </p>
+            </div>
+            <Button
+              variant="ghost"
+              size="icon"
+              aria-label="Cancel scheduled notification"
+              onClick={() => cancel(item.id)}
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+        ))
+      )}
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button variant="ghost" onClick={load}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}
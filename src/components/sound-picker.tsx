@@ -0,0 +1,82 @@
+import { invoke } from "@tauri-apps/api/core";
+import { Play } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+
+export const selectClassName =
+  "h-9 rounded-md border border-border bg-muted/40 px-2 text-sm text-foreground";
+
+const CUSTOM = "__custom__";
+
+type SoundPickerProps = {
+  label: string;
+  value: string | null;
+  bundled: string[];
+  // Shown for the empty choice, e.g. "Default sound".
+  emptyLabel: string;
+  onChange: (value: string | null) => void;
+  onError?: (message: string) => void;
+};
+
+/** Picks a bundled sound by name or an audio file by path, with a preview button. */
+export function SoundPicker({
+  label,
+  value,
+  bundled,
+  emptyLabel,
+  onChange,
+  onError,
+}: SoundPickerProps) {
+  const isCustom = value !== null && !bundled.includes(value);
+
+  async function preview() {
+    if (!value) return;
+    try {
+      await invoke("preview_sound", { sound: value });
+    } catch (err) {
+      console.error(err);
+      onError?.(String(err));
+    }
+  }
+
+  return (
+    <div className="flex flex-1 flex-wrap items-center gap-2">
+      <select
+        aria-label={label}
+        value={isCustom ? CUSTOM : (value ?? "")}
+        onChange={(e) => {
+          const next = e.currentTarget.value;
+          onChange(next === CUSTOM ? "" : next || null);
+        }}
+        className={selectClassName}
+      >
+        <option value="">{emptyLabel}</option>
+        {bundled.map((sound) => (
+          <option key={sound} value={sound}>
+            {sound}
+          </option>
+        ))}
+        <option value={CUSTOM}>Custom file…</option>
+      </select>
+      {isCustom && (
+        <Input
+          aria-label={`${label} file`}
+          value={value ?? ""}
+          onChange={(e) => onChange(e.currentTarget.value)}
+          placeholder="/path/to/sound.mp3"
+          className="min-w-[180px] flex-1 bg-muted/40 text-foreground placeholder:text-muted-foreground"
+        />
+      )}
+      <Button
+        variant="ghost"
+        size="icon"
+        aria-label={`Preview ${label}`}
+        onClick={preview}
+        disabled={!value}
+      >
+        <Play className="h-4 w-4" />
+      </Button>
+    </div>
+  );
+}
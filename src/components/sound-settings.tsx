@@ -0,0 +1,282 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Loader2, Plus, Trash2 } from "lucide-react";
+
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { SoundPicker, selectClassName } from "@/components/sound-picker";
+
+type AudioSettings = {
+  muted: boolean;
+  volume: number;
+  output_device: string | null;
+  default_sound: string | null;
+  agent_sounds: Record<string, string>;
+};
+
+type AgentSound = { agent: string; sound: string | null };
+
+type Status = { type: "success" | "error"; message: string } | null;
+
+export function SoundSettingsForm() {
+  const [settings, setSettings] = useState<AudioSettings | null>(null);
+  const [agentSounds, setAgentSounds] = useState<AgentSound[]>([]);
+  const [sounds, setSounds] = useState<string[]>([]);
+  const [devices, setDevices] = useState<string[]>([]);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<Status>(null);
+
+  useEffect(() => {
+    load();
+    // The tray menu can mute or unmute while this form is open.
+    const unlisten = listen<boolean>("mute-changed", (event) => {
+      setSettings((prev) => (prev ? { ...prev, muted: event.payload } : prev));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  async function load() {
+    setStatus(null);
+    try {
+      const [audio, bundled, outputs] = await Promise.all([
+        invoke<AudioSettings>("get_audio_settings"),
+        invoke<string[]>("list_bundled_sounds"),
+        invoke<string[]>("list_output_devices"),
+      ]);
+      setDevices(outputs);
+      setSettings(audio);
+      setAgentSounds(
+        Object.entries(audio.agent_sounds).map(([agent, sound]) => ({
+          agent,
+          sound,
+        })),
+      );
+      setSounds(bundled);
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: "Failed to load sound settings" });
+    }
+  }
+
+  function updateAgent(index: number, patch: Partial<AgentSound>) {
+    setAgentSounds((prev) =>
+      prev.map((entry, i) => (i === index ? { ...entry, ...patch } : entry)),
+    );
+    setStatus(null);
+  }
+
+  async function save() {
+    if (!settings) return;
+    setSaving(true);
+    setStatus(null);
+    try {
+      const agent_sounds: Record<string, string> = {};
+      for (const { agent, sound } of agentSounds) {
+        if (agent.trim() && sound) {
+          agent_sounds[agent.trim()] = sound.trim();
+        }
+      }
+      await invoke("save_audio_settings", {
+        settings: {
+          ...settings,
+          default_sound: settings.default_sound?.trim() || null,
+          agent_sounds,
+        },
+      });
+      setStatus({ type: "success", message: "Sounds saved" });
+    } catch (err) {
+      console.error(err);
+      setStatus({ type: "error", message: String(err) });
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  if (!settings) {
+    return (
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading sounds…
+      </div>
+    );
+  }
+
+  const showError = (message: string) => setStatus({ type: "error", message });
+
+  return (
+    <div className="space-y-5">
+      <div className="divide-y divide-border overflow-hidden rounded-2xl border border-border bg-card">
+        <label className="flex cursor-pointer items-center justify-between gap-4 px-4 py-4">
+          <div className="space-y-1">
+            <p className="text-sm font-medium text-foreground">Mute sounds</p>
+            <p className="text-xs text-muted-foreground">
+              Notifications still appear. Also available from the tray menu.
+            </p>
+          </div>
+          <input
+            type="checkbox"
+            checked={settings.muted}
+            onChange={(e) => {
+              setSettings({ ...settings, muted: e.currentTarget.checked });
+              setStatus(null);
+            }}
+            className="h-4 w-4 accent-current"
+          />
+        </label>
+
+        <div className="flex flex-wrap items-center justify-between gap-4 px-4 py-4">
+          <Label htmlFor="sound-volume" className="text-foreground">
+            Volume
+          </Label>
+          <div className="flex min-w-[220px] flex-1 items-center gap-3 sm:max-w-md">
+            <input
+              id="sound-volume"
+              type="range"
+              min={0}
+              max={1}
+              step={0.05}
+              value={settings.volume}
+              onChange={(e) => {
+                setSettings({ ...settings, volume: Number(e.currentTarget.value) });
+                setStatus(null);
+              }}
+              className="flex-1 accent-current"
+            />
+            <span className="w-10 text-right text-sm text-muted-foreground">
+              {Math.round(settings.volume * 100)}%
+            </span>
+          </div>
+        </div>
+
+        <div className="flex flex-wrap items-center justify-between gap-4 px-4 py-4">
+          <div className="space-y-1">
+            <Label htmlFor="sound-device" className="text-foreground">
+              Output device
+            </Label>
+            <p className="text-xs text-muted-foreground">
+              Falls back to the system default when unplugged.
+            </p>
+          </div>
+          <select
+            id="sound-device"
+            value={settings.output_device ?? ""}
+            onChange={(e) => {
+              setSettings({
+                ...settings,
+                output_device: e.currentTarget.value || null,
+              });
+              setStatus(null);
+            }}
+            className={`${selectClassName} min-w-[220px] flex-1 sm:max-w-md`}
+          >
+            <option value="">System default</option>
+            {settings.output_device &&
+              !devices.includes(settings.output_device) && (
+                <option value={settings.output_device}>
+                  {settings.output_device} (not connected)
+                </option>
+              )}
+            {devices.map((device) => (
+              <option key={device} value={device}>
+                {device}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <div className="flex flex-wrap items-center justify-between gap-4 px-4 py-4">
+          <div className="space-y-1">
+            <Label className="text-foreground">Default sound</Label>
+            <p className="text-xs text-muted-foreground">
+              Used when neither the agent nor the level picks one.
+            </p>
+          </div>
+          <div className="flex min-w-[220px] flex-1 sm:max-w-md">
+            <SoundPicker
+              label="Default sound"
+              value={settings.default_sound}
+              bundled={sounds}
+              emptyLabel="Ping (built in)"
+              onChange={(default_sound) => {
+                setSettings({ ...settings, default_sound });
+                setStatus(null);
+              }}
+              onError={showError}
+            />
+          </div>
+        </div>
+
+        {agentSounds.map((entry, index) => (
+          <div
+            key={index}
+            className="flex flex-wrap items-center justify-between gap-4 px-4 py-4"
+          >
+            <Input
+              aria-label="Agent"
+              value={entry.agent}
+              onChange={(e) =>
+                updateAgent(index, { agent: e.currentTarget.value })
+              }
+              placeholder="Agent, e.g. codex"
+              className="w-40 bg-muted/40 text-foreground placeholder:text-muted-foreground"
+            />
+            <SoundPicker
+              label={`Sound for ${entry.agent || "agent"}`}
+              value={entry.sound}
+              bundled={sounds}
+              emptyLabel="Choose a sound"
+              onChange={(sound) => updateAgent(index, { sound })}
+              onError={showError}
+            />
+            <Button
+              variant="ghost"
+              size="icon"
+              aria-label="Remove agent sound"
+              onClick={() =>
+                setAgentSounds((prev) => prev.filter((_, i) => i !== index))
+              }
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+        ))}
+      </div>
+
+      {status && (
+        <div
+          className={`rounded-xl border px-4 py-3 text-sm ${
+            status.type === "success"
+              ? "border-ring/60 bg-muted/60 text-foreground"
+              : "border-destructive/60 bg-destructive/10 text-destructive-foreground"
+          }`}
+        >
+          {status.message}
+        </div>
+      )}
+
+      <div className="flex flex-wrap gap-3">
+        <Button onClick={save} disabled={saving}>
+          {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Save
+        </Button>
+        <Button
+          variant="secondary"
+          onClick={() =>
+            setAgentSounds((prev) => [...prev, { agent: "", sound: null }])
+          }
+          disabled={saving}
+        >
+          <Plus className="h-4 w-4" />
+          Add agent sound
+        </Button>
+        <Button variant="ghost" onClick={load} disabled={saving}>
+          Reload
+        </Button>
+      </div>
+    </div>
+  );
+}